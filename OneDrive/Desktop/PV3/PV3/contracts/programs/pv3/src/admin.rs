@@ -2,29 +2,73 @@
 // Will be expanded with admin panel functionality, multisig controls, etc.
 
 use anchor_lang::prelude::*;
-use crate::{PlatformConfig, PV3Error, SessionVault};
+use anchor_lang::solana_program::hash::hash;
+use crate::{FeePolicy, PlatformConfig, PV3Error, SessionVault, MAX_WAGER_TIERS};
 
 #[derive(Accounts)]
-pub struct InitializeAdmin<'info> {
+pub struct EmergencyAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin_signers.contains(&signer.key()) @ PV3Error::UnauthorizedAdmin
+    )]
+    pub config: Account<'info, PlatformConfig>,
+    
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FundCrankBudget<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin_signers.contains(&signer.key()) @ PV3Error::UnauthorizedAdmin
+    )]
+    pub config: Account<'info, PlatformConfig>,
+    
     #[account(mut)]
-    pub payer: Signer<'info>,
+    pub signer: Signer<'info>,
     
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: String)]
+pub struct CreateGameConfig<'info> {
     #[account(
         init,
-        payer = payer,
-        space = AdminConfig::SIZE,
-        seeds = [b"admin"],
+        payer = signer,
+        space = 8 + GameConfig::INIT_SPACE,
+        seeds = [b"game_config", hash(game_id.as_bytes()).as_ref()],
         bump
     )]
-    pub admin_config: Account<'info, AdminConfig>,
+    pub game_config: Account<'info, GameConfig>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin_signers.contains(&signer.key()) @ PV3Error::UnauthorizedAdmin
+    )]
+    pub config: Account<'info, PlatformConfig>,
+    
+    #[account(mut)]
+    pub signer: Signer<'info>,
     
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct EmergencyAction<'info> {
+pub struct UpdateGameConfig<'info> {
     #[account(
         mut,
+        seeds = [b"game_config", hash(game_config.game_id.as_bytes()).as_ref()],
+        bump = game_config.bump
+    )]
+    pub game_config: Account<'info, GameConfig>,
+    
+    #[account(
         seeds = [b"config"],
         bump = config.bump,
         constraint = config.admin_signers.contains(&signer.key()) @ PV3Error::UnauthorizedAdmin
@@ -64,24 +108,15 @@ pub struct RecoverVault<'info> {
     pub admin2: Signer<'info>,
 }
 
+/// Per-game overrides of the platform wager bounds, seeded by sha256(game_id)
 #[account]
-pub struct AdminConfig {
-    pub treasury: Pubkey,
-    pub referral_pool: Pubkey,
-    pub is_paused: bool,
-    pub admin_authority: Pubkey,
-}
-
-impl AdminConfig {
-    pub const SIZE: usize = 32 + 32 + 1 + 32 + 8;
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub enum EmergencyActionType {
-    PauseMatches,
-    UnpauseMatches,
-    RecoverStuckFunds,
-    UpdatePlatformFee,
+#[derive(InitSpace)]
+pub struct GameConfig {
+    #[max_len(50)]
+    pub game_id: String,
+    pub min_wager: u64,
+    pub max_wager: u64,
+    pub bump: u8,
 }
 
 // Admin functions
@@ -168,6 +203,294 @@ pub fn update_fees(
     Ok(())
 }
 
+pub fn update_draw_fee_policy(
+    ctx: Context<EmergencyAction>,
+    draw_fee_policy: FeePolicy,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let admin = &ctx.accounts.signer;
+    
+    let old_policy = config.draw_fee_policy;
+    config.draw_fee_policy = draw_fee_policy;
+    
+    emit!(DrawFeePolicyUpdated {
+        admin: admin.key(),
+        old_policy,
+        new_policy: draw_fee_policy,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Draw fee policy updated by admin: {}", admin.key());
+    
+    Ok(())
+}
+
+pub fn update_abandonment_fee_policy(
+    ctx: Context<EmergencyAction>,
+    abandonment_fee_policy: FeePolicy,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let admin = &ctx.accounts.signer;
+    
+    let old_policy = config.abandonment_fee_policy;
+    config.abandonment_fee_policy = abandonment_fee_policy;
+    
+    emit!(AbandonmentFeePolicyUpdated {
+        admin: admin.key(),
+        old_policy,
+        new_policy: abandonment_fee_policy,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Abandonment fee policy updated by admin: {}", admin.key());
+    
+    Ok(())
+}
+
+pub fn update_crank_bounty(ctx: Context<EmergencyAction>, crank_bounty: u64) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let admin = &ctx.accounts.signer;
+    
+    let old_bounty = config.crank_bounty;
+    config.crank_bounty = crank_bounty;
+    
+    emit!(CrankBountyUpdated {
+        admin: admin.key(),
+        old_bounty,
+        new_bounty: crank_bounty,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Crank bounty updated by admin: {} - {} lamports", admin.key(), crank_bounty);
+    
+    Ok(())
+}
+
+pub fn fund_crank_budget(ctx: Context<FundCrankBudget>, amount: u64) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let admin = &ctx.accounts.signer;
+    
+    require!(amount > 0, PV3Error::InvalidAmount);
+    
+    // Bounty lamports are held by the config account itself, tracked by crank_budget
+    let transfer_instruction = anchor_lang::system_program::Transfer {
+        from: admin.to_account_info(),
+        to: config.to_account_info(),
+    };
+    
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            transfer_instruction,
+        ),
+        amount,
+    )?;
+    
+    config.crank_budget += amount;
+    
+    emit!(CrankBudgetFunded {
+        admin: admin.key(),
+        amount,
+        new_budget: config.crank_budget,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Crank budget funded by admin: {} - {} lamports", admin.key(), amount);
+    
+    Ok(())
+}
+
+pub fn update_challenge_period(
+    ctx: Context<EmergencyAction>,
+    challenge_period: i64,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let admin = &ctx.accounts.signer;
+    
+    // 0 disables the window, cap at 7 days so payouts can't be frozen indefinitely
+    require!(
+        (0..=7 * 24 * 3600).contains(&challenge_period),
+        PV3Error::InvalidChallengePeriod
+    );
+    
+    let old_challenge_period = config.challenge_period;
+    config.challenge_period = challenge_period;
+    
+    emit!(ChallengePeriodUpdated {
+        admin: admin.key(),
+        old_challenge_period,
+        new_challenge_period: challenge_period,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!(
+        "Challenge period updated by admin: {} - {}s",
+        admin.key(),
+        challenge_period
+    );
+    
+    Ok(())
+}
+
+pub fn update_wager_bounds(
+    ctx: Context<EmergencyAction>,
+    min_wager: u64,
+    max_wager: u64,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let admin = &ctx.accounts.signer;
+    
+    require!(
+        min_wager > 0 && min_wager <= max_wager,
+        PV3Error::InvalidWagerBounds
+    );
+    // Narrowing the bounds must not strand a configured tier outside them
+    require!(
+        tiers_within_bounds(&config.wager_tiers, min_wager, max_wager),
+        PV3Error::InvalidWagerBounds
+    );
+    
+    let old_min_wager = config.min_wager;
+    let old_max_wager = config.max_wager;
+    
+    config.min_wager = min_wager;
+    config.max_wager = max_wager;
+    
+    emit!(WagerBoundsUpdated {
+        admin: admin.key(),
+        game_id: None,
+        old_min_wager,
+        new_min_wager: min_wager,
+        old_max_wager,
+        new_max_wager: max_wager,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!(
+        "Wager bounds updated by admin: {} - Min: {} lamports, Max: {} lamports",
+        admin.key(),
+        min_wager,
+        max_wager
+    );
+    
+    Ok(())
+}
+
+pub fn update_wager_tiers(
+    ctx: Context<EmergencyAction>,
+    wager_tiers: Vec<u64>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let admin = &ctx.accounts.signer;
+    
+    // Tiers must be non-zero and strictly ascending, an empty list disables them
+    require!(wager_tiers.len() <= MAX_WAGER_TIERS, PV3Error::InvalidWagerTiers);
+    require!(
+        !wager_tiers.contains(&0),
+        PV3Error::InvalidWagerTiers
+    );
+    require!(
+        wager_tiers.windows(2).all(|pair| pair[0] < pair[1]),
+        PV3Error::InvalidWagerTiers
+    );
+    require!(
+        tiers_within_bounds(&wager_tiers, config.min_wager, config.max_wager),
+        PV3Error::InvalidWagerTiers
+    );
+    
+    let old_tiers = std::mem::replace(&mut config.wager_tiers, wager_tiers.clone());
+    
+    emit!(WagerTiersUpdated {
+        admin: admin.key(),
+        old_tiers,
+        new_tiers: wager_tiers,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Wager tiers updated by admin: {}", admin.key());
+    
+    Ok(())
+}
+
+// A tier outside the platform bounds could never be wagered
+fn tiers_within_bounds(wager_tiers: &[u64], min_wager: u64, max_wager: u64) -> bool {
+    wager_tiers.iter().all(|tier| (min_wager..=max_wager).contains(tier))
+}
+
+pub fn create_game_config(
+    ctx: Context<CreateGameConfig>,
+    game_id: String,
+    min_wager: u64,
+    max_wager: u64,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let game_config = &mut ctx.accounts.game_config;
+    let admin = &ctx.accounts.signer;
+    
+    require!(
+        min_wager > 0 && min_wager <= max_wager,
+        PV3Error::InvalidWagerBounds
+    );
+    
+    game_config.game_id = game_id.clone();
+    game_config.min_wager = min_wager;
+    game_config.max_wager = max_wager;
+    game_config.bump = ctx.bumps.game_config;
+    
+    // Old values are the platform-wide bounds the game used until now
+    emit!(WagerBoundsUpdated {
+        admin: admin.key(),
+        game_id: Some(game_id),
+        old_min_wager: config.min_wager,
+        new_min_wager: min_wager,
+        old_max_wager: config.max_wager,
+        new_max_wager: max_wager,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+pub fn update_game_wager_bounds(
+    ctx: Context<UpdateGameConfig>,
+    min_wager: u64,
+    max_wager: u64,
+) -> Result<()> {
+    let game_config = &mut ctx.accounts.game_config;
+    let admin = &ctx.accounts.signer;
+    
+    require!(
+        min_wager > 0 && min_wager <= max_wager,
+        PV3Error::InvalidWagerBounds
+    );
+    
+    let old_min_wager = game_config.min_wager;
+    let old_max_wager = game_config.max_wager;
+    
+    game_config.min_wager = min_wager;
+    game_config.max_wager = max_wager;
+    
+    emit!(WagerBoundsUpdated {
+        admin: admin.key(),
+        game_id: Some(game_config.game_id.clone()),
+        old_min_wager,
+        new_min_wager: min_wager,
+        old_max_wager,
+        new_max_wager: max_wager,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!(
+        "Wager bounds for {} updated by admin: {} - Min: {} lamports, Max: {} lamports",
+        game_config.game_id,
+        admin.key(),
+        min_wager,
+        max_wager
+    );
+    
+    Ok(())
+}
+
 pub fn recover_inactive_vault(
     ctx: Context<RecoverVault>,
     inactivity_threshold_hours: u64,
@@ -254,6 +577,65 @@ pub struct FeesUpdated {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct DrawFeePolicyUpdated {
+    pub admin: Pubkey,
+    pub old_policy: FeePolicy,
+    pub new_policy: FeePolicy,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AbandonmentFeePolicyUpdated {
+    pub admin: Pubkey,
+    pub old_policy: FeePolicy,
+    pub new_policy: FeePolicy,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CrankBountyUpdated {
+    pub admin: Pubkey,
+    pub old_bounty: u64,
+    pub new_bounty: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CrankBudgetFunded {
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub new_budget: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ChallengePeriodUpdated {
+    pub admin: Pubkey,
+    pub old_challenge_period: i64,
+    pub new_challenge_period: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WagerBoundsUpdated {
+    pub admin: Pubkey,
+    pub game_id: Option<String>, // None for the platform-wide bounds
+    pub old_min_wager: u64,
+    pub new_min_wager: u64,
+    pub old_max_wager: u64,
+    pub new_max_wager: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WagerTiersUpdated {
+    pub admin: Pubkey,
+    pub old_tiers: Vec<u64>,
+    pub new_tiers: Vec<u64>,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct VaultRecovered {
     pub vault_owner: Pubkey,
@@ -0,0 +1,360 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions;
+use crate::{
+    check_refund_expired, create_result_message, distribute_winnings, pay_crank_bounty,
+    record_ready_no_shows, record_settlement, refund_deadline, refund_target,
+    require_escrow_covers, require_unsettled, return_to_source, verify_verifier_signature, Escrow,
+    ExpiredMatchCranked, Match, MatchCompleted, MatchRefunded, MatchResultPending, MatchStatus,
+    PlatformConfig, PV3Error,
+};
+
+// Accounts passed per match through remaining_accounts
+pub const REFUND_ENTRY_ACCOUNTS: usize = 4; // match, escrow, creator refund target, joiner refund target
+pub const SETTLEMENT_ENTRY_ACCOUNTS: usize = 3; // match, escrow, winner
+
+/// Batch Processor - Refunds expired matches and settles verified results in bulk.
+/// Every entry is validated on its own and skipped with an outcome event when it
+/// fails, so one bad entry never reverts the rest of the batch
+#[derive(Accounts)]
+pub struct BatchRefundMatches<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+
+    // Any caller; receives the crank bounty for every match refunded
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BatchSubmitResults<'info> {
+    #[account(mut, address = config.treasury)]
+    /// CHECK: Treasury account for platform fees
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, address = config.referral_pool)]
+    /// CHECK: Referral pool account
+    pub referral_pool: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+
+    /// CHECK: This is the instructions sysvar
+    #[account(address = instructions::ID)]
+    pub instruction_sysvar: AccountInfo<'info>,
+}
+
+// One verifier-signed verdict, matched by position to its remaining_accounts entry.
+// Each verdict needs its own ed25519 instruction in the transaction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchResult {
+    pub winner: Pubkey,
+    pub result_hash: [u8; 32],
+    pub ed25519_signature: [u8; 64],
+    pub ed25519_instruction_index: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BatchOutcome {
+    Refunded,
+    Settled,
+    PendingSettlement,  // Verdict recorded, payout waits out the challenge period
+    Skipped,
+}
+
+// Batch functions
+pub fn batch_refund_matches<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchRefundMatches<'info>>,
+) -> Result<()> {
+    let entries = ctx.remaining_accounts;
+    require!(
+        !entries.is_empty() && entries.chunks_exact(REFUND_ENTRY_ACCOUNTS).remainder().is_empty(),
+        PV3Error::InvalidBatchAccounts
+    );
+
+    let current_time = Clock::get()?.unix_timestamp;
+    let mut refunded: u32 = 0;
+    let mut skipped: u32 = 0;
+
+    for entry in entries.chunks(REFUND_ENTRY_ACCOUNTS) {
+        let match_id = entry[0].key();
+
+        match refund_entry(&mut ctx.accounts.config, &ctx.accounts.keeper, entry, current_time) {
+            Ok((refund_amount, keeper_bounty)) => {
+                refunded += 1;
+
+                emit!(MatchRefunded {
+                    match_id,
+                    refund_amount,
+                });
+
+                emit!(ExpiredMatchCranked {
+                    match_id,
+                    keeper: ctx.accounts.keeper.key(),
+                    keeper_bounty,
+                    remaining_budget: ctx.accounts.config.crank_budget,
+                });
+
+                emit!(BatchEntryProcessed {
+                    match_id,
+                    outcome: BatchOutcome::Refunded,
+                    error_code: 0,
+                });
+            }
+            Err(error) => {
+                skipped += 1;
+                emit_skipped(match_id, &error);
+            }
+        }
+    }
+
+    msg!("Batch refund: {} refunded, {} skipped", refunded, skipped);
+    Ok(())
+}
+
+pub fn batch_submit_results<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchSubmitResults<'info>>,
+    results: Vec<BatchResult>,
+) -> Result<()> {
+    let entries = ctx.remaining_accounts;
+    require!(
+        !results.is_empty() && entries.len() == results.len() * SETTLEMENT_ENTRY_ACCOUNTS,
+        PV3Error::InvalidBatchAccounts
+    );
+
+    let mut settled: u32 = 0;
+    let mut skipped: u32 = 0;
+
+    for (entry, result) in entries.chunks(SETTLEMENT_ENTRY_ACCOUNTS).zip(results.iter()) {
+        let match_id = entry[0].key();
+
+        match settle_entry(ctx.accounts, entry, result) {
+            Ok(outcome) => {
+                settled += 1;
+
+                emit!(BatchEntryProcessed {
+                    match_id,
+                    outcome,
+                    error_code: 0,
+                });
+            }
+            Err(error) => {
+                skipped += 1;
+                emit_skipped(match_id, &error);
+            }
+        }
+    }
+
+    msg!("Batch settlement: {} settled, {} skipped", settled, skipped);
+    Ok(())
+}
+
+// Refunds a single expired match and pays the keeper's bounty for it. Everything that
+// can fail is checked before the first lamport moves, so a skipped entry leaves its
+// accounts untouched. Returns the total amount refunded and the bounty paid
+fn refund_entry<'info>(
+    config: &mut Account<'info, PlatformConfig>,
+    keeper: &AccountInfo<'info>,
+    entry: &'info [AccountInfo<'info>],
+    current_time: i64,
+) -> Result<(u64, u64)> {
+    require_writable(entry)?;
+
+    let mut match_account = Account::<Match>::try_from(&entry[0])?;
+    let mut match_escrow = load_match_escrow(&entry[1], &match_account)?;
+
+    let has_joiner = check_refund_expired(&match_account, current_time)?;
+    let expired_at = refund_deadline(&match_account);
+
+    let wager_amount = match_account.wager_amount;
+    let refund_amount = if has_joiner { wager_amount * 2 } else { wager_amount };
+    require_escrow_covers(&match_escrow, refund_amount)?;
+
+    let mut creator_session_vault = refund_target(
+        match_account.creator_funding,
+        match_account.creator,
+        &entry[2],
+    )?;
+    let mut joiner_session_vault = if has_joiner {
+        refund_target(match_account.joiner_funding, match_account.joiner, &entry[3])?
+    } else {
+        None
+    };
+
+    if match_account.status == MatchStatus::AwaitingReady {
+        record_ready_no_shows(&mut match_account);
+    }
+
+    return_to_source(
+        &mut match_escrow,
+        match_account.creator_funding,
+        &entry[2],
+        creator_session_vault.as_mut(),
+        wager_amount,
+    )?;
+
+    if has_joiner {
+        return_to_source(
+            &mut match_escrow,
+            match_account.joiner_funding,
+            &entry[3],
+            joiner_session_vault.as_mut(),
+            wager_amount,
+        )?;
+    }
+
+    match_account.status = MatchStatus::Refunded;
+
+    // Accounts loaded from remaining_accounts aren't written back automatically
+    match_account.exit(&crate::ID)?;
+    match_escrow.exit(&crate::ID)?;
+    if let Some(session_vault) = &creator_session_vault {
+        session_vault.exit(&crate::ID)?;
+    }
+    if let Some(session_vault) = &joiner_session_vault {
+        session_vault.exit(&crate::ID)?;
+    }
+
+    let keeper_bounty = pay_crank_bounty(config, keeper, &match_account, expired_at, current_time)?;
+
+    Ok((refund_amount, keeper_bounty))
+}
+
+// Settles a single match from its verified verdict, mirroring submit_result
+fn settle_entry<'info>(
+    accounts: &mut BatchSubmitResults<'info>,
+    entry: &'info [AccountInfo<'info>],
+    result: &BatchResult,
+) -> Result<BatchOutcome> {
+    require_writable(entry)?;
+
+    let mut match_account = Account::<Match>::try_from(&entry[0])?;
+    let mut match_escrow = load_match_escrow(&entry[1], &match_account)?;
+
+    require_unsettled(&match_account.status)?;
+    require!(
+        match_account.status == MatchStatus::InProgress,
+        PV3Error::MatchNotInProgress
+    );
+    require!(
+        result.winner == match_account.creator || result.winner == match_account.joiner,
+        PV3Error::InvalidWinner
+    );
+    require!(match_account.series_length == 1, PV3Error::SeriesResultRequired);
+    require!(!match_account.is_async, PV3Error::ScoreResultRequired);
+
+    let expected_message = create_result_message(
+        match_account.key(),
+        result.winner,
+        result.result_hash,
+    );
+
+    verify_verifier_signature(
+        &accounts.instruction_sysvar,
+        result.ed25519_instruction_index,
+        &accounts.config.verifier_pubkey,
+        &result.ed25519_signature,
+        &expected_message,
+    )?;
+
+    match_account.winner = result.winner;
+    match_account.result_hash = result.result_hash;
+
+    // With a challenge period configured, hold the payout until finalize_match
+    if match_account.hold_for_challenge(&accounts.config)? {
+        match_account.exit(&crate::ID)?;
+
+        emit!(MatchResultPending {
+            match_id: match_account.key(),
+            winner: result.winner,
+            settle_after: match_account.settle_after,
+        });
+
+        return Ok(BatchOutcome::PendingSettlement);
+    }
+
+    let winner = &entry[2];
+    require_keys_eq!(winner.key(), result.winner, PV3Error::InvalidWinner);
+
+    let total_pot = match_account.total_pot;
+    require_escrow_covers(&match_escrow, total_pot)?;
+
+    match_account.status = MatchStatus::Completed;
+
+    let (winner_amount, platform_fee, treasury_fee, referral_fee) = distribute_winnings(
+        &accounts.config,
+        total_pot,
+        &mut match_escrow,
+        winner,
+        &accounts.treasury,
+        &accounts.referral_pool,
+    )?;
+
+    match_account.exit(&crate::ID)?;
+    match_escrow.exit(&crate::ID)?;
+
+    record_settlement(&mut accounts.config, total_pot);
+
+    emit!(MatchCompleted {
+        match_id: match_account.key(),
+        wager_tier: match_account.wager_tier,
+        winner: result.winner,
+        winner_amount,
+        platform_fee,
+        treasury_fee,
+        referral_fee,
+    });
+
+    Ok(BatchOutcome::Settled)
+}
+
+// Loads the escrow for a match. Escrows are program-owned and record their match
+// at init, so a matching `game` is as strong as re-deriving the PDA
+fn load_match_escrow<'info>(
+    escrow_info: &'info AccountInfo<'info>,
+    match_account: &Account<'info, Match>,
+) -> Result<Account<'info, Escrow>> {
+    let match_escrow = Account::<Escrow>::try_from(escrow_info)?;
+    require_keys_eq!(match_escrow.game, match_account.key(), PV3Error::EscrowMismatch);
+    Ok(match_escrow)
+}
+
+// A read-only account would fail the whole transaction once written, so the entry is
+// skipped up front instead
+fn require_writable(entry: &[AccountInfo]) -> Result<()> {
+    require!(
+        entry.iter().all(|account| account.is_writable),
+        PV3Error::BatchEntryNotWritable
+    );
+    Ok(())
+}
+
+fn emit_skipped(match_id: Pubkey, error: &Error) {
+    let error_code = match error {
+        Error::AnchorError(error) => error.error_code_number as u64,
+        Error::ProgramError(error) => u64::from(error.program_error.clone()),
+    };
+
+    msg!("Batch entry {} skipped: {}", match_id, error);
+
+    emit!(BatchEntryProcessed {
+        match_id,
+        outcome: BatchOutcome::Skipped,
+        error_code,
+    });
+}
+
+// Events
+#[event]
+pub struct BatchEntryProcessed {
+    pub match_id: Pubkey,
+    pub outcome: BatchOutcome,
+    pub error_code: u64, // 0 unless the entry was skipped
+}
@@ -0,0 +1,330 @@
+use anchor_lang::prelude::*;
+use crate::{
+    distribute_draw, record_settlement, return_to_source, settle_recorded_winner, Escrow, Match,
+    MatchDrawn, MatchRefunded, MatchStatus, PlatformConfig, PlayerProfile, PV3Error,
+    SessionVault,
+};
+
+/// Dispute Manager - Lets a player freeze a match escrow with an evidence hash
+/// (e.g. replay log digest) until the 2-of-3 admin multisig arbitrates it
+#[derive(Accounts)]
+pub struct OpenDispute<'info> {
+    #[account(
+        init,
+        payer = player,
+        space = 8 + Dispute::INIT_SPACE,
+        seeds = [b"dispute", match_account.key().as_ref()],
+        bump
+    )]
+    pub dispute: Account<'info, Dispute>,
+    
+    #[account(
+        mut,
+        constraint = match_account.creator == player.key() ||
+            match_account.joiner == player.key() @ PV3Error::InvalidParticipant
+    )]
+    pub match_account: Account<'info, Match>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    #[account(
+        mut,
+        seeds = [b"dispute", match_account.key().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Account<'info, Dispute>,
+    
+    #[account(mut)]
+    pub match_account: Account<'info, Match>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", match_account.key().as_ref()],
+        bump = match_escrow.bump
+    )]
+    pub match_escrow: Account<'info, Escrow>,
+    
+    #[account(mut, address = match_account.creator @ PV3Error::InvalidParticipant)]
+    /// CHECK: Creator account, paid or refunded depending on the resolution
+    pub creator: AccountInfo<'info>,
+    
+    #[account(mut, address = match_account.joiner @ PV3Error::InvalidParticipant)]
+    /// CHECK: Joiner account, paid or refunded depending on the resolution
+    pub joiner: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"session", match_account.creator.as_ref()],
+        bump = creator_session_vault.bump
+    )]
+    pub creator_session_vault: Option<Account<'info, SessionVault>>,
+    
+    #[account(
+        mut,
+        seeds = [b"session", match_account.joiner.as_ref()],
+        bump = joiner_session_vault.bump
+    )]
+    pub joiner_session_vault: Option<Account<'info, SessionVault>>,
+    
+    // Counted against the abandoner once an abandonment verdict pays out
+    #[account(
+        mut,
+        seeds = [b"profile", match_account.abandoned_by.as_ref()],
+        bump = abandoner_profile.bump
+    )]
+    pub abandoner_profile: Option<Account<'info, PlayerProfile>>,
+    
+    #[account(mut, address = config.treasury)]
+    /// CHECK: Treasury account for platform fees
+    pub treasury: AccountInfo<'info>,
+    
+    #[account(mut, address = config.referral_pool)]
+    /// CHECK: Referral pool account
+    pub referral_pool: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin_signers.contains(&admin1.key()) @ PV3Error::UnauthorizedAdmin,
+        constraint = config.admin_signers.contains(&admin2.key()) @ PV3Error::UnauthorizedAdmin,
+        constraint = admin1.key() != admin2.key() @ PV3Error::InsufficientAdminSignatures
+    )]
+    pub config: Account<'info, PlatformConfig>,
+    
+    // 2-of-3 multisig requirement
+    pub admin1: Signer<'info>,
+    pub admin2: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Dispute {
+    pub match_account: Pubkey,
+    pub opened_by: Pubkey,
+    pub evidence_hash: [u8; 32],
+    pub opened_at: i64,
+    pub verdict_pending: bool, // A win or draw was recorded when the dispute froze the match
+    pub resolution: Option<DisputeResolution>,
+    pub resolved_at: i64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum DisputeResolution {
+    UpholdResult,   // Pay the verdict recorded on the match
+    ReverseResult,  // Pay the other participant instead
+    Refund,         // Nullify the match and return both wagers
+}
+
+// Dispute functions
+pub fn open_dispute(ctx: Context<OpenDispute>, evidence_hash: [u8; 32]) -> Result<()> {
+    let dispute = &mut ctx.accounts.dispute;
+    let match_account = &mut ctx.accounts.match_account;
+    let player = &ctx.accounts.player;
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    // Only matches whose escrow is still funded can be frozen
+    let verdict_pending = match_account.status != MatchStatus::InProgress;
+    match match_account.status {
+        MatchStatus::InProgress | MatchStatus::Flagged => {}
+        MatchStatus::PendingSettlement => {
+            require!(
+                current_time < match_account.settle_after,
+                PV3Error::ChallengePeriodEnded
+            );
+        }
+        _ => return err!(PV3Error::DisputeNotAllowed),
+    }
+    
+    match_account.status = MatchStatus::Disputed;
+    
+    dispute.match_account = match_account.key();
+    dispute.opened_by = player.key();
+    dispute.evidence_hash = evidence_hash;
+    dispute.opened_at = current_time;
+    dispute.verdict_pending = verdict_pending;
+    dispute.resolution = None;
+    dispute.resolved_at = 0;
+    dispute.bump = ctx.bumps.dispute;
+    
+    emit!(DisputeOpened {
+        match_id: match_account.key(),
+        dispute: dispute.key(),
+        opened_by: player.key(),
+        evidence_hash,
+        timestamp: current_time,
+    });
+    
+    msg!("Dispute opened on match {} by {}", match_account.key(), player.key());
+    Ok(())
+}
+
+pub fn resolve_dispute(ctx: Context<ResolveDispute>, resolution: DisputeResolution) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let dispute = &mut ctx.accounts.dispute;
+    let match_account = &mut ctx.accounts.match_account;
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    require!(
+        match_account.status == MatchStatus::Disputed,
+        PV3Error::MatchNotDisputed
+    );
+    
+    let total_pot = match_account.total_pot;
+    
+    let winner = match resolution {
+        DisputeResolution::UpholdResult
+            if dispute.verdict_pending && match_account.winner == Pubkey::default() =>
+        {
+            // Upholding a pending draw splits the pot as the verdict said
+            match_account.status = MatchStatus::Drawn;
+            
+            let (creator_amount, joiner_amount, platform_fee, treasury_fee, referral_fee) = distribute_draw(
+                config,
+                match_account,
+                &mut ctx.accounts.match_escrow,
+                &ctx.accounts.creator,
+                ctx.accounts.creator_session_vault.as_mut(),
+                &ctx.accounts.joiner,
+                ctx.accounts.joiner_session_vault.as_mut(),
+                &ctx.accounts.treasury,
+                &ctx.accounts.referral_pool,
+            )?;
+            
+            record_settlement(&mut ctx.accounts.config, total_pot);
+            
+            emit!(MatchDrawn {
+                match_id: match_account.key(),
+                wager_tier: match_account.wager_tier,
+                creator_amount,
+                joiner_amount,
+                platform_fee,
+                treasury_fee,
+                referral_fee,
+            });
+            
+            Pubkey::default()
+        }
+        DisputeResolution::UpholdResult | DisputeResolution::ReverseResult => {
+            // Disputes opened mid-game have no verdict to uphold or reverse, and a
+            // draw has no other player to reverse it to
+            require!(
+                match_account.winner != Pubkey::default(),
+                PV3Error::NoVerdictToArbitrate
+            );
+            
+            let pay_creator = (match_account.winner == match_account.creator)
+                == (resolution == DisputeResolution::UpholdResult);
+            let winner_account = if pay_creator {
+                &ctx.accounts.creator
+            } else {
+                &ctx.accounts.joiner
+            };
+            
+            // A reversed abandonment verdict no longer counts against anyone
+            if resolution == DisputeResolution::ReverseResult {
+                match_account.abandoned_by = Pubkey::default();
+            }
+            
+            match_account.winner = winner_account.key();
+            match_account.status = MatchStatus::Completed;
+            
+            settle_recorded_winner(
+                config,
+                match_account,
+                &mut ctx.accounts.match_escrow,
+                winner_account,
+                &ctx.accounts.treasury,
+                &ctx.accounts.referral_pool,
+                ctx.accounts.abandoner_profile.as_mut(),
+            )?;
+            
+            record_settlement(&mut ctx.accounts.config, total_pot);
+            
+            match_account.winner
+        }
+        DisputeResolution::Refund => {
+            let wager_amount = match_account.wager_amount;
+            require!(
+                ctx.accounts.match_escrow.balance >= total_pot,
+                PV3Error::InsufficientEscrowBalance
+            );
+            
+            return_to_source(
+                &mut ctx.accounts.match_escrow,
+                match_account.creator_funding,
+                &ctx.accounts.creator,
+                ctx.accounts.creator_session_vault.as_mut(),
+                wager_amount,
+            )?;
+            
+            return_to_source(
+                &mut ctx.accounts.match_escrow,
+                match_account.joiner_funding,
+                &ctx.accounts.joiner,
+                ctx.accounts.joiner_session_vault.as_mut(),
+                wager_amount,
+            )?;
+            
+            match_account.winner = Pubkey::default();
+            match_account.status = MatchStatus::Refunded;
+            
+            emit!(MatchRefunded {
+                match_id: match_account.key(),
+                refund_amount: total_pot,
+            });
+            
+            Pubkey::default()
+        }
+    };
+    
+    dispute.resolution = Some(resolution);
+    dispute.resolved_at = current_time;
+    
+    emit!(DisputeResolved {
+        match_id: match_account.key(),
+        dispute: dispute.key(),
+        resolution,
+        winner,
+        admin1: ctx.accounts.admin1.key(),
+        admin2: ctx.accounts.admin2.key(),
+        timestamp: current_time,
+    });
+    
+    msg!(
+        "Dispute resolved on match {} by admins: {}, {}",
+        match_account.key(),
+        ctx.accounts.admin1.key(),
+        ctx.accounts.admin2.key()
+    );
+    
+    Ok(())
+}
+
+// Events
+#[event]
+pub struct DisputeOpened {
+    pub match_id: Pubkey,
+    pub dispute: Pubkey,
+    pub opened_by: Pubkey,
+    pub evidence_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub match_id: Pubkey,
+    pub dispute: Pubkey,
+    pub resolution: DisputeResolution,
+    pub winner: Pubkey, // Pubkey::default() for refunds and draws
+    pub admin1: Pubkey,
+    pub admin2: Pubkey,
+    pub timestamp: i64,
+}
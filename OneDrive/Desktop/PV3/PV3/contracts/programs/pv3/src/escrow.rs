@@ -0,0 +1,234 @@
+use anchor_lang::prelude::*;
+use crate::{FundingSource, PV3Error, SessionVault};
+
+/// Escrow Vault - Program-owned account holding the wagers for a single match,
+/// lobby or team match. The account keeps its rent-exempt reserve for its whole
+/// life, and every lamport above that reserve is accounted for in `balance`
+#[account]
+#[derive(InitSpace)]
+pub struct Escrow {
+    pub game: Pubkey,           // Match, lobby or team match this vault belongs to
+    pub balance: u64,           // Wager lamports currently held
+    pub total_deposited: u64,
+    pub total_released: u64,
+    pub bump: u8,
+}
+
+// Sets up a freshly initialized vault with an empty ledger
+pub fn init_escrow(escrow: &mut Account<Escrow>, game: Pubkey, bump: u8) {
+    escrow.game = game;
+    escrow.balance = 0;
+    escrow.total_deposited = 0;
+    escrow.total_released = 0;
+    escrow.bump = bump;
+}
+
+// Moves a wager from the player's wallet into the vault and records it
+pub fn deposit_to_escrow<'info>(
+    escrow: &mut Account<'info, Escrow>,
+    player: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    let transfer_instruction = anchor_lang::system_program::Transfer {
+        from: player.to_account_info(),
+        to: escrow.to_account_info(),
+    };
+
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            transfer_instruction,
+        ),
+        amount,
+    )?;
+
+    escrow.balance += amount;
+    escrow.total_deposited += amount;
+
+    Ok(())
+}
+
+// Moves a wager out of the player's session vault. Both accounts belong to this
+// program, so lamports are moved directly; a system transfer can't debit a data account
+pub fn deposit_from_session(
+    escrow: &mut Account<Escrow>,
+    session_vault: &mut Account<SessionVault>,
+    amount: u64,
+) -> Result<()> {
+    require!(
+        session_vault.balance >= amount,
+        PV3Error::InsufficientSessionBalance
+    );
+
+    // The vault's recorded balance must be backed by lamports above its own rent reserve
+    let vault_info = session_vault.to_account_info();
+    let rent_reserve = Rent::get()?.minimum_balance(vault_info.data_len());
+    require!(
+        vault_info.lamports() >= rent_reserve + amount,
+        PV3Error::InsufficientSessionBalance
+    );
+
+    **vault_info.try_borrow_mut_lamports()? -= amount;
+    **escrow.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    session_vault.balance -= amount;
+    escrow.balance += amount;
+    escrow.total_deposited += amount;
+
+    Ok(())
+}
+
+// Checks the vault can pay out `amount` in total, both on its ledger and in
+// lamports above the rent-exempt reserve
+pub fn require_escrow_covers(escrow: &Account<Escrow>, amount: u64) -> Result<()> {
+    require!(escrow.balance >= amount, PV3Error::InsufficientEscrowBalance);
+
+    let escrow_info = escrow.to_account_info();
+    let rent_reserve = Rent::get()?.minimum_balance(escrow_info.data_len());
+    require!(
+        escrow_info.lamports() >= rent_reserve + amount,
+        PV3Error::InsufficientEscrowBalance
+    );
+
+    Ok(())
+}
+
+// Pays out of the vault. The vault is program-owned, so it can be debited directly,
+// but never below what the ledger holds or into the rent-exempt reserve
+pub fn release_from_escrow(
+    escrow: &mut Account<Escrow>,
+    recipient: &AccountInfo,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    require_escrow_covers(escrow, amount)?;
+
+    let escrow_info = escrow.to_account_info();
+    **escrow_info.try_borrow_mut_lamports()? -= amount;
+    **recipient.try_borrow_mut_lamports()? += amount;
+
+    escrow.balance -= amount;
+    escrow.total_released += amount;
+
+    Ok(())
+}
+
+// Returns a player's share to wherever their wager came from, crediting the session
+// vault's balance when it was session-funded so its bookkeeping stays in sync
+pub fn return_to_source(
+    escrow: &mut Account<Escrow>,
+    funding_source: FundingSource,
+    wallet: &AccountInfo,
+    session_vault: Option<&mut Account<SessionVault>>,
+    amount: u64,
+) -> Result<()> {
+    match funding_source {
+        FundingSource::Wallet => release_from_escrow(escrow, wallet, amount),
+        FundingSource::SessionVault => {
+            let session_vault = session_vault.ok_or(PV3Error::SessionVaultRequired)?;
+            release_from_escrow(escrow, &session_vault.to_account_info(), amount)?;
+            session_vault.balance += amount;
+            Ok(())
+        }
+    }
+}
+
+// Checks a refund target against the side's funding source: the player's wallet,
+// or the session vault their wager was drawn from
+pub fn refund_target<'info>(
+    funding_source: FundingSource,
+    player: Pubkey,
+    target: &'info AccountInfo<'info>,
+) -> Result<Option<Account<'info, SessionVault>>> {
+    match funding_source {
+        FundingSource::Wallet => {
+            require_keys_eq!(target.key(), player, PV3Error::InvalidParticipant);
+            Ok(None)
+        }
+        FundingSource::SessionVault => {
+            let session_vault = Account::<SessionVault>::try_from(target)?;
+            require_keys_eq!(session_vault.owner, player, PV3Error::InvalidParticipant);
+            Ok(Some(session_vault))
+        }
+    }
+}
+
+// return_to_source for a target passed through remaining_accounts, which has to be
+// checked and, when it is a session vault, written back by hand
+pub fn return_to_target<'info>(
+    escrow: &mut Account<'info, Escrow>,
+    funding_source: FundingSource,
+    player: Pubkey,
+    target: &'info AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let mut session_vault = refund_target(funding_source, player, target)?;
+    return_to_source(escrow, funding_source, target, session_vault.as_mut(), amount)?;
+
+    if let Some(session_vault) = &session_vault {
+        session_vault.exit(&crate::ID)?;
+    }
+
+    Ok(())
+}
+
+// Sends the platform fee's treasury and referral shares out of the vault
+pub fn release_platform_fee(
+    escrow: &mut Account<Escrow>,
+    treasury: &AccountInfo,
+    referral_pool: &AccountInfo,
+    treasury_fee: u64,
+    referral_fee: u64,
+) -> Result<()> {
+    release_from_escrow(escrow, treasury, treasury_fee)?;
+    release_from_escrow(escrow, referral_pool, referral_fee)
+}
+
+// Splits the net pot over the winners by basis points, the first winner taking any
+// rounding remainder so it is paid out in full. Targets line up with the winners.
+// Returns each winner's payout
+pub fn pay_shares<'info>(
+    escrow: &mut Account<'info, Escrow>,
+    net_pot: u64,
+    winners: &[(Pubkey, FundingSource)],
+    share_bps: &[u16],
+    targets: &'info [AccountInfo<'info>],
+) -> Result<Vec<u64>> {
+    let mut payouts: Vec<u64> = share_bps
+        .iter()
+        .map(|bps| net_pot * *bps as u64 / 10000)
+        .collect();
+    payouts[0] += net_pot - payouts.iter().sum::<u64>();
+
+    for (((player, funding_source), target), amount) in
+        winners.iter().zip(targets.iter()).zip(payouts.iter())
+    {
+        return_to_target(escrow, *funding_source, *player, target, *amount)?;
+    }
+
+    Ok(payouts)
+}
+
+// Returns every entry's wager to its funding source. Targets line up with the entries
+pub fn refund_entries<'info>(
+    escrow: &mut Account<'info, Escrow>,
+    entries: &[(Pubkey, FundingSource)],
+    targets: &'info [AccountInfo<'info>],
+    wager_amount: u64,
+) -> Result<()> {
+    require!(targets.len() == entries.len(), PV3Error::InvalidParticipant);
+    require!(
+        escrow.balance >= wager_amount * entries.len() as u64,
+        PV3Error::InsufficientEscrowBalance
+    );
+
+    for ((player, funding_source), target) in entries.iter().zip(targets.iter()) {
+        return_to_target(escrow, *funding_source, *player, target, wager_amount)?;
+    }
+
+    Ok(())
+}
@@ -1,16 +1,34 @@
+// #[program] emits its IDL instruction handlers, which still call AccountInfo::realloc,
+// into a module at the crate root, so no narrower item can carry this allow
+#![allow(deprecated)]
+
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::{
     ed25519_program,
-    sysvar::instructions::{self, Instruction},
+    hash::hash,
+    instruction::Instruction,
+    sysvar::instructions,
 };
 
 mod admin;
-mod verifier;
 mod session;
+mod dispute;
+mod profile;
+mod lobby;
+mod team;
+mod escrow;
+mod batch;
+#[cfg(test)]
+mod tests;
 
 use admin::*;
-use verifier::*;
 use session::*;
+use dispute::*;
+use profile::*;
+use lobby::*;
+use team::*;
+use escrow::*;
+use batch::*;
 
 declare_id!("51mQPjsgLs5XpPMmtux9jmTaRqbsi36jKoDGADfjzbDs");
 
@@ -37,8 +55,17 @@ pub mod pv3 {
         config.platform_fee_bps = 650; // 6.5% = 650 basis points
         config.treasury_fee_bps = 550; // 5.5% to treasury
         config.referral_fee_bps = 100; // 1% to referrals
+        config.draw_fee_policy = FeePolicy::NoFee;
+        config.abandonment_fee_policy = FeePolicy::NormalFee;
+        config.challenge_period = 0; // Pay out immediately until enabled
+        config.crank_bounty = 0;
+        config.crank_budget = 0;
+        config.min_wager = 100_000_000; // 0.1 SOL
+        config.max_wager = 10_000_000_000; // 10 SOL
+        config.wager_tiers = DEFAULT_WAGER_TIERS.to_vec();
         config.is_paused = false;
         config.total_matches = 0;
+        config.next_match_number = 1;
         config.total_volume = 0;
         config.admin_signers = [admin1, admin2, admin3];
         config.bump = ctx.bumps.config;
@@ -54,70 +81,213 @@ pub mod pv3 {
 
     // ============= MATCH MANAGEMENT =============
     
+    #[allow(clippy::too_many_arguments)]
     pub fn create_match(
         ctx: Context<CreateMatch>,
+        nonce: u64,
         game_id: String,
         wager_amount: u64,
-        expiry_time: i64,
+        join_deadline: i64,
+        result_window: i64,
         use_session_vault: bool,
+        invited_opponent: Option<Pubkey>,
+        join_secret_hash: Option<[u8; 32]>,
+        series_length: u8,
+        is_async: bool,
+        ready_window: i64,
     ) -> Result<()> {
         let config = &ctx.accounts.config;
         let match_account = &mut ctx.accounts.match_account;
         let creator = &ctx.accounts.creator;
         
         require!(!config.is_paused, PV3Error::PlatformPaused);
-        require!(wager_amount >= 100_000_000, PV3Error::WagerTooLow); // Min 0.1 SOL
-        require!(wager_amount <= 10_000_000_000, PV3Error::WagerTooHigh); // Max 10 SOL
-        require!(expiry_time > Clock::get()?.unix_timestamp, PV3Error::InvalidExpiryTime);
+        require_wager_in_bounds(config, &ctx.accounts.game_config, wager_amount)?;
+        let wager_tier = wager_tier_index(config, wager_amount)?;
+        require!(join_deadline > Clock::get()?.unix_timestamp, PV3Error::InvalidExpiryTime);
+        require!(
+            (1..=MAX_RESULT_WINDOW).contains(&result_window),
+            PV3Error::InvalidResultWindow
+        );
+        require!(
+            series_length % 2 == 1 && series_length <= MAX_SERIES_LENGTH,
+            PV3Error::InvalidSeriesLength
+        );
+        // Async matches compare one score per player, so they are always a single game
+        require!(!is_async || series_length == 1, PV3Error::AsyncSeriesNotSupported);
+        // Zero skips the ready check and starts the match as soon as it is joined
+        require!(
+            (0..=MAX_READY_WINDOW).contains(&ready_window),
+            PV3Error::InvalidReadyWindow
+        );
+        require!(
+            invited_opponent != Some(creator.key()),
+            PV3Error::CannotJoinOwnMatch
+        );
+        
+        // The nonce must be the creator's next one, so a retried create lands on
+        // the same address instead of opening a second match
+        let creator_profile = &mut ctx.accounts.creator_profile;
+        require!(nonce == creator_profile.match_nonce, PV3Error::InvalidMatchNonce);
+        creator_profile.match_nonce += 1;
+        
+        let match_number = next_match_number(&mut ctx.accounts.config);
         
         match_account.creator = creator.key();
         match_account.joiner = Pubkey::default();
         match_account.game_id = game_id.clone();
         match_account.wager_amount = wager_amount;
+        match_account.wager_tier = wager_tier;
         match_account.total_pot = wager_amount;
-        match_account.expiry_time = expiry_time;
+        match_account.join_deadline = join_deadline;
+        match_account.result_window = result_window;
+        match_account.result_deadline = 0; // Set once an opponent joins
         match_account.status = MatchStatus::WaitingForPlayer;
         match_account.winner = Pubkey::default();
         match_account.created_at = Clock::get()?.unix_timestamp;
         match_account.result_hash = [0u8; 32];
+        match_account.invited_opponent = invited_opponent.unwrap_or_default();
+        match_account.join_secret_hash = join_secret_hash.unwrap_or_default();
+        match_account.rematch_of = Pubkey::default();
+        match_account.creator_nonce = nonce;
+        match_account.match_number = match_number;
+        match_account.series_length = series_length;
+        match_account.creator_wins = 0;
+        match_account.joiner_wins = 0;
+        match_account.is_async = is_async;
+        match_account.creator_score = None;
+        match_account.joiner_score = None;
+        match_account.ready_window = ready_window;
+        match_account.ready_deadline = 0;
+        match_account.creator_ready = false;
+        match_account.joiner_ready = false;
+        match_account.creator_no_show = false;
+        match_account.joiner_no_show = false;
+        match_account.abandoned_by = Pubkey::default();
         match_account.bump = ctx.bumps.match_account;
         
-        if use_session_vault {
-            // Use funds from creator's session vault
-            let session_vault = &mut ctx.accounts.session_vault.as_mut().unwrap();
-            require!(
-                session_vault.balance >= wager_amount,
-                PV3Error::InsufficientSessionBalance
-            );
-            
-            session_vault.balance -= wager_amount;
-            session_vault.matches_played += 1;
-            session_vault.last_activity = Clock::get()?.unix_timestamp;
-        } else {
-            // Direct SOL transfer from creator
-            let transfer_instruction = anchor_lang::system_program::Transfer {
-                from: creator.to_account_info(),
-                to: ctx.accounts.match_escrow.to_account_info(),
-            };
-            
-            anchor_lang::system_program::transfer(
-                CpiContext::new(
-                    ctx.accounts.system_program.to_account_info(),
-                    transfer_instruction,
-                ),
-                wager_amount,
-            )?;
-        }
+        init_escrow(&mut ctx.accounts.match_escrow, match_account.key(), ctx.bumps.match_escrow);
         
-        // Always transfer to match escrow
-        **ctx.accounts.match_escrow.to_account_info().try_borrow_mut_lamports()? += wager_amount;
+        let funding_source = fund_wager(
+            use_session_vault,
+            wager_amount,
+            ctx.accounts.session_vault.as_mut(),
+            creator,
+            &mut ctx.accounts.match_escrow,
+            &ctx.accounts.system_program,
+        )?;
+        match_account.creator_funding = funding_source;
+        match_account.joiner_funding = FundingSource::Wallet; // Replaced once an opponent joins
         
         emit!(MatchCreated {
             match_id: match_account.key(),
+            match_number,
             creator: creator.key(),
+            nonce,
             game_id,
             wager_amount,
-            expiry_time,
+            wager_tier,
+            join_deadline,
+            result_window,
+            invited_opponent: match_account.invited_opponent,
+            requires_join_secret: join_secret_hash.is_some(),
+            series_length,
+            is_async,
+            ready_window,
+            funding_source,
+        });
+        
+        Ok(())
+    }
+
+    pub fn create_rematch(
+        ctx: Context<CreateRematch>,
+        join_deadline: i64,
+        use_session_vault: bool,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let previous_match = &ctx.accounts.previous_match;
+        let match_account = &mut ctx.accounts.match_account;
+        let creator = &ctx.accounts.creator;
+        
+        require!(!config.is_paused, PV3Error::PlatformPaused);
+        require!(
+            previous_match.status == MatchStatus::Completed ||
+            previous_match.status == MatchStatus::Drawn,
+            PV3Error::RematchNotAllowed
+        );
+        require!(join_deadline > Clock::get()?.unix_timestamp, PV3Error::InvalidExpiryTime);
+        
+        // Same pairing, game and stake; only the previous opponent may join
+        let opponent = if creator.key() == previous_match.creator {
+            previous_match.joiner
+        } else {
+            previous_match.creator
+        };
+        let wager_amount = previous_match.wager_amount;
+        
+        // Limits may have changed since the original match
+        require_wager_in_bounds(config, &ctx.accounts.game_config, wager_amount)?;
+        let wager_tier = wager_tier_index(config, wager_amount)?;
+        
+        let match_number = next_match_number(&mut ctx.accounts.config);
+        
+        match_account.creator = creator.key();
+        match_account.joiner = Pubkey::default();
+        match_account.game_id = previous_match.game_id.clone();
+        match_account.wager_amount = wager_amount;
+        match_account.wager_tier = wager_tier;
+        match_account.total_pot = wager_amount;
+        match_account.join_deadline = join_deadline;
+        match_account.result_window = previous_match.result_window;
+        match_account.result_deadline = 0; // Set once the opponent joins
+        match_account.status = MatchStatus::WaitingForPlayer;
+        match_account.winner = Pubkey::default();
+        match_account.created_at = Clock::get()?.unix_timestamp;
+        match_account.result_hash = [0u8; 32];
+        match_account.invited_opponent = opponent;
+        match_account.join_secret_hash = [0u8; 32];
+        match_account.rematch_of = previous_match.key();
+        match_account.creator_nonce = 0; // Rematches are seeded by the previous match
+        match_account.match_number = match_number;
+        match_account.series_length = previous_match.series_length;
+        match_account.creator_wins = 0;
+        match_account.joiner_wins = 0;
+        match_account.is_async = previous_match.is_async;
+        match_account.creator_score = None;
+        match_account.joiner_score = None;
+        match_account.ready_window = previous_match.ready_window;
+        match_account.ready_deadline = 0;
+        match_account.creator_ready = false;
+        match_account.joiner_ready = false;
+        match_account.creator_no_show = false;
+        match_account.joiner_no_show = false;
+        match_account.abandoned_by = Pubkey::default();
+        match_account.bump = ctx.bumps.match_account;
+        
+        init_escrow(&mut ctx.accounts.match_escrow, match_account.key(), ctx.bumps.match_escrow);
+        
+        let funding_source = fund_wager(
+            use_session_vault,
+            wager_amount,
+            ctx.accounts.session_vault.as_mut(),
+            creator,
+            &mut ctx.accounts.match_escrow,
+            &ctx.accounts.system_program,
+        )?;
+        match_account.creator_funding = funding_source;
+        match_account.joiner_funding = FundingSource::Wallet; // Replaced once the opponent joins
+        
+        emit!(RematchCreated {
+            match_id: match_account.key(),
+            match_number,
+            previous_match: previous_match.key(),
+            creator: creator.key(),
+            opponent,
+            game_id: match_account.game_id.clone(),
+            wager_amount,
+            wager_tier,
+            join_deadline,
+            funding_source,
         });
         
         Ok(())
@@ -126,6 +296,7 @@ pub mod pv3 {
     pub fn join_match(
         ctx: Context<JoinMatch>,
         use_session_vault: bool,
+        join_secret: Option<Vec<u8>>,
     ) -> Result<()> {
         let config = &ctx.accounts.config;
         let match_account = &mut ctx.accounts.match_account;
@@ -141,50 +312,101 @@ pub mod pv3 {
             PV3Error::CannotJoinOwnMatch
         );
         require!(
-            Clock::get()?.unix_timestamp < match_account.expiry_time,
+            Clock::get()?.unix_timestamp < match_account.join_deadline,
             PV3Error::MatchExpired
         );
         
-        let wager_amount = match_account.wager_amount;
-        
-        if use_session_vault {
-            // Use funds from joiner's session vault
-            let session_vault = &mut ctx.accounts.session_vault.as_mut().unwrap();
+        // Invite-only matches: restrict to the designated opponent and/or
+        // require the preimage of the join secret hash
+        if match_account.invited_opponent != Pubkey::default() {
             require!(
-                session_vault.balance >= wager_amount,
-                PV3Error::InsufficientSessionBalance
+                match_account.invited_opponent == joiner.key(),
+                PV3Error::NotInvitedOpponent
+            );
+        }
+        if match_account.join_secret_hash != [0u8; 32] {
+            let secret = join_secret.ok_or(PV3Error::InvalidJoinSecret)?;
+            require!(
+                hash(&secret).to_bytes() == match_account.join_secret_hash,
+                PV3Error::InvalidJoinSecret
             );
-            
-            session_vault.balance -= wager_amount;
-            session_vault.matches_played += 1;
-            session_vault.last_activity = Clock::get()?.unix_timestamp;
-        } else {
-            // Direct SOL transfer from joiner
-            let transfer_instruction = anchor_lang::system_program::Transfer {
-                from: joiner.to_account_info(),
-                to: ctx.accounts.match_escrow.to_account_info(),
-            };
-            
-            anchor_lang::system_program::transfer(
-                CpiContext::new(
-                    ctx.accounts.system_program.to_account_info(),
-                    transfer_instruction,
-                ),
-                wager_amount,
-            )?;
         }
+        // Async challengers play against a posted score
+        if match_account.is_async {
+            require!(match_account.creator_score.is_some(), PV3Error::AwaitingCreatorScore);
+        }
+        
+        let wager_amount = match_account.wager_amount;
         
-        // Always transfer to match escrow
-        **ctx.accounts.match_escrow.to_account_info().try_borrow_mut_lamports()? += wager_amount;
+        let funding_source = fund_wager(
+            use_session_vault,
+            wager_amount,
+            ctx.accounts.session_vault.as_mut(),
+            joiner,
+            &mut ctx.accounts.match_escrow,
+            &ctx.accounts.system_program,
+        )?;
+        match_account.joiner_funding = funding_source;
         
         match_account.joiner = joiner.key();
         match_account.total_pot = wager_amount * 2;
-        match_account.status = MatchStatus::InProgress;
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        if match_account.ready_window > 0 {
+            // Both players must confirm through ready before the game starts
+            match_account.status = MatchStatus::AwaitingReady;
+            match_account.ready_deadline = current_time + match_account.ready_window;
+        } else {
+            match_account.status = MatchStatus::InProgress;
+            // The clock for reporting a result starts when the game does
+            match_account.result_deadline = current_time + match_account.result_window;
+        }
         
         emit!(MatchJoined {
             match_id: match_account.key(),
             joiner: joiner.key(),
             total_pot: match_account.total_pot,
+            ready_deadline: match_account.ready_deadline,
+            result_deadline: match_account.result_deadline,
+            funding_source,
+        });
+        
+        Ok(())
+    }
+
+    pub fn ready(ctx: Context<Ready>) -> Result<()> {
+        let match_account = &mut ctx.accounts.match_account;
+        let player = &ctx.accounts.player;
+        
+        require!(
+            match_account.status == MatchStatus::AwaitingReady,
+            PV3Error::NotAwaitingReady
+        );
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            current_time <= match_account.ready_deadline,
+            PV3Error::ReadyWindowClosed
+        );
+        
+        if player.key() == match_account.creator {
+            require!(!match_account.creator_ready, PV3Error::AlreadyReady);
+            match_account.creator_ready = true;
+        } else {
+            require!(!match_account.joiner_ready, PV3Error::AlreadyReady);
+            match_account.joiner_ready = true;
+        }
+        
+        let started = match_account.creator_ready && match_account.joiner_ready;
+        if started {
+            match_account.status = MatchStatus::InProgress;
+            match_account.result_deadline = current_time + match_account.result_window;
+        }
+        
+        emit!(PlayerReady {
+            match_id: match_account.key(),
+            player: player.key(),
+            started,
+            result_deadline: match_account.result_deadline,
         });
         
         Ok(())
@@ -195,12 +417,13 @@ pub mod pv3 {
         winner_pubkey: Pubkey,
         result_hash: [u8; 32],
         ed25519_signature: [u8; 64],
-        ed25519_recovery_id: u8,
+        _ed25519_recovery_id: u8,
         ed25519_instruction_index: u8,
     ) -> Result<()> {
         let config = &ctx.accounts.config;
         let match_account = &mut ctx.accounts.match_account;
         
+        require_unsettled(&match_account.status)?;
         require!(
             match_account.status == MatchStatus::InProgress,
             PV3Error::MatchNotInProgress
@@ -209,70 +432,149 @@ pub mod pv3 {
             winner_pubkey == match_account.creator || winner_pubkey == match_account.joiner,
             PV3Error::InvalidWinner
         );
+        // Series matches are reported game by game through submit_game_result
+        require!(match_account.series_length == 1, PV3Error::SeriesResultRequired);
+        require!(!match_account.is_async, PV3Error::ScoreResultRequired);
+        
+        // Verify the signature is for our match result
+        let expected_message = create_result_message(
+            match_account.key(),
+            winner_pubkey,
+            result_hash,
+        );
         
-        // Verify ed25519 signature from verifier service
-        let instruction_sysvar = &ctx.accounts.instruction_sysvar;
-        let current_instruction = instructions::get_instruction_relative(
-            ed25519_instruction_index as i64, 
-            instruction_sysvar
+        verify_verifier_signature(
+            &ctx.accounts.instruction_sysvar,
+            ed25519_instruction_index,
+            &config.verifier_pubkey,
+            &ed25519_signature,
+            &expected_message,
         )?;
         
+        settle_verified_result(ctx.accounts, winner_pubkey, result_hash)
+    }
+
+    pub fn submit_game_result(
+        ctx: Context<SubmitResult>,
+        game_number: u8,
+        winner_pubkey: Pubkey,
+        result_hash: [u8; 32],
+        ed25519_signature: [u8; 64],
+        ed25519_instruction_index: u8,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let match_account = &mut ctx.accounts.match_account;
+        
+        require_unsettled(&match_account.status)?;
+        require!(
+            match_account.status == MatchStatus::InProgress,
+            PV3Error::MatchNotInProgress
+        );
+        require!(match_account.series_length > 1, PV3Error::NotASeriesMatch);
+        require!(
+            winner_pubkey == match_account.creator || winner_pubkey == match_account.joiner,
+            PV3Error::InvalidWinner
+        );
+        // Games are reported strictly in order, which also stops signature replays
         require!(
-            current_instruction.program_id == ed25519_program::ID,
-            PV3Error::InvalidSignatureInstruction
+            game_number == match_account.creator_wins + match_account.joiner_wins + 1,
+            PV3Error::InvalidGameNumber
         );
         
-        // Verify the signature is for our match result
-        let expected_message = create_result_message(
+        let expected_message = create_game_result_message(
             match_account.key(),
+            game_number,
             winner_pubkey,
             result_hash,
         );
         
-        verify_ed25519_signature(
+        verify_verifier_signature(
+            &ctx.accounts.instruction_sysvar,
+            ed25519_instruction_index,
+            &config.verifier_pubkey,
             &ed25519_signature,
             &expected_message,
-            config.verifier_pubkey.as_ref(),
-            &current_instruction,
         )?;
         
-        // Update match with verified result
-        match_account.winner = winner_pubkey;
-        match_account.result_hash = result_hash;
+        if winner_pubkey == match_account.creator {
+            match_account.creator_wins += 1;
+        } else {
+            match_account.joiner_wins += 1;
+        }
+        
+        emit!(SeriesGameRecorded {
+            match_id: match_account.key(),
+            game_number,
+            winner: winner_pubkey,
+            result_hash,
+            creator_wins: match_account.creator_wins,
+            joiner_wins: match_account.joiner_wins,
+        });
+        
+        // The pot is only released once one side holds a majority of the series
+        let wins_needed = match_account.series_length / 2 + 1;
+        let winner_wins = match_account.creator_wins.max(match_account.joiner_wins);
+        if winner_wins < wins_needed {
+            return Ok(());
+        }
+        
+        settle_verified_result(ctx.accounts, winner_pubkey, result_hash)
+    }
+
+    pub fn finalize_match(ctx: Context<FinalizeMatch>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let match_account = &mut ctx.accounts.match_account;
+        
+        require_finalizable(&match_account.status, match_account.settle_after)?;
+        require!(match_account.winner != Pubkey::default(), PV3Error::PendingDraw);
+        
         match_account.status = MatchStatus::Completed;
         
-        // Calculate fees according to whitepaper: 6.5% total (5.5% treasury, 1% referral)
         let total_pot = match_account.total_pot;
-        let platform_fee = (total_pot * config.platform_fee_bps as u64) / 10000;
-        let treasury_fee = (total_pot * config.treasury_fee_bps as u64) / 10000;
-        let referral_fee = (total_pot * config.referral_fee_bps as u64) / 10000;
-        let winner_amount = total_pot - platform_fee;
+        settle_recorded_winner(
+            config,
+            match_account,
+            &mut ctx.accounts.match_escrow,
+            &ctx.accounts.winner,
+            &ctx.accounts.treasury,
+            &ctx.accounts.referral_pool,
+            ctx.accounts.abandoner_profile.as_mut(),
+        )?;
+        
+        record_settlement(&mut ctx.accounts.config, total_pot);
         
-        // Transfer funds from escrow
-        let escrow_balance = ctx.accounts.match_escrow.to_account_info().lamports();
-        require!(escrow_balance >= total_pot, PV3Error::InsufficientEscrowBalance);
+        Ok(())
+    }
+
+    pub fn finalize_draw(ctx: Context<FinalizeDraw>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let match_account = &mut ctx.accounts.match_account;
         
-        // Transfer to winner
-        **ctx.accounts.match_escrow.to_account_info().try_borrow_mut_lamports()? -= winner_amount;
-        **ctx.accounts.winner.to_account_info().try_borrow_mut_lamports()? += winner_amount;
+        require_finalizable(&match_account.status, match_account.settle_after)?;
+        require!(match_account.winner == Pubkey::default(), PV3Error::NotAPendingDraw);
         
-        // Transfer treasury fee
-        **ctx.accounts.match_escrow.to_account_info().try_borrow_mut_lamports()? -= treasury_fee;
-        **ctx.accounts.treasury.to_account_info().try_borrow_mut_lamports()? += treasury_fee;
+        match_account.status = MatchStatus::Drawn;
         
-        // Transfer referral fee
-        **ctx.accounts.match_escrow.to_account_info().try_borrow_mut_lamports()? -= referral_fee;
-        **ctx.accounts.referral_pool.to_account_info().try_borrow_mut_lamports()? += referral_fee;
+        let total_pot = match_account.total_pot;
+        let (creator_amount, joiner_amount, platform_fee, treasury_fee, referral_fee) = distribute_draw(
+            config,
+            match_account,
+            &mut ctx.accounts.match_escrow,
+            &ctx.accounts.creator,
+            ctx.accounts.creator_session_vault.as_mut(),
+            &ctx.accounts.joiner,
+            ctx.accounts.joiner_session_vault.as_mut(),
+            &ctx.accounts.treasury,
+            &ctx.accounts.referral_pool,
+        )?;
         
-        // Update global stats
-        let config = &mut ctx.accounts.config;
-        config.total_matches += 1;
-        config.total_volume += total_pot;
+        record_settlement(&mut ctx.accounts.config, total_pot);
         
-        emit!(MatchCompleted {
+        emit!(MatchDrawn {
             match_id: match_account.key(),
-            winner: winner_pubkey,
-            winner_amount,
+            wager_tier: match_account.wager_tier,
+            creator_amount,
+            joiner_amount,
             platform_fee,
             treasury_fee,
             referral_fee,
@@ -281,137 +583,2258 @@ pub mod pv3 {
         Ok(())
     }
 
-    pub fn refund_match(ctx: Context<RefundMatch>) -> Result<()> {
+    pub fn flag_match(ctx: Context<FlagMatch>) -> Result<()> {
+        let config = &ctx.accounts.config;
         let match_account = &mut ctx.accounts.match_account;
+        let flagger = &ctx.accounts.flagger;
+        
+        require_flaggable(
+            &match_account.status,
+            match_account.settle_after,
+            Clock::get()?.unix_timestamp,
+        )?;
         
+        // Only the losing player or a platform admin may hold the payout. A pending
+        // draw has no loser, so either player may
+        let player_may_flag = if match_account.winner == Pubkey::default() {
+            flagger.key() == match_account.creator || flagger.key() == match_account.joiner
+        } else if match_account.winner == match_account.creator {
+            flagger.key() == match_account.joiner
+        } else {
+            flagger.key() == match_account.creator
+        };
         require!(
-            Clock::get()?.unix_timestamp > match_account.expiry_time ||
-            match_account.status == MatchStatus::Cancelled,
-            PV3Error::RefundNotAllowed
+            player_may_flag || config.admin_signers.contains(&flagger.key()),
+            PV3Error::UnauthorizedFlag
         );
         
-        let wager_amount = match_account.wager_amount;
-        let escrow_balance = ctx.accounts.match_escrow.to_account_info().lamports();
+        match_account.status = MatchStatus::Flagged;
+        match_account.flagged_by = flagger.key();
         
-        // Refund creator
-        if escrow_balance >= wager_amount {
-            **ctx.accounts.match_escrow.to_account_info().try_borrow_mut_lamports()? -= wager_amount;
-            **ctx.accounts.creator.to_account_info().try_borrow_mut_lamports()? += wager_amount;
-        }
+        emit!(MatchFlagged {
+            match_id: match_account.key(),
+            flagged_by: flagger.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
         
-        // Refund joiner if they joined
-        if match_account.joiner != Pubkey::default() && escrow_balance >= wager_amount {
-            **ctx.accounts.match_escrow.to_account_info().try_borrow_mut_lamports()? -= wager_amount;
-            **ctx.accounts.joiner.to_account_info().try_borrow_mut_lamports()? += wager_amount;
-        }
+        Ok(())
+    }
+
+    pub fn resolve_flagged_match(
+        ctx: Context<ResolveFlaggedMatch>,
+        release_payout: bool,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let match_account = &mut ctx.accounts.match_account;
         
-        match_account.status = MatchStatus::Refunded;
+        require!(
+            match_account.status == MatchStatus::Flagged,
+            PV3Error::MatchNotFlagged
+        );
         
-        emit!(MatchRefunded {
+        let total_pot = match_account.total_pot;
+        
+        if release_payout && match_account.winner == Pubkey::default() {
+            // Flag dismissed on a draw, split the pot as the verdict said
+            match_account.status = MatchStatus::Drawn;
+            
+            let (creator_amount, joiner_amount, platform_fee, treasury_fee, referral_fee) = distribute_draw(
+                config,
+                match_account,
+                &mut ctx.accounts.match_escrow,
+                &ctx.accounts.creator,
+                ctx.accounts.creator_session_vault.as_mut(),
+                &ctx.accounts.joiner,
+                ctx.accounts.joiner_session_vault.as_mut(),
+                &ctx.accounts.treasury,
+                &ctx.accounts.referral_pool,
+            )?;
+            
+            record_settlement(&mut ctx.accounts.config, total_pot);
+            
+            emit!(MatchDrawn {
+                match_id: match_account.key(),
+                wager_tier: match_account.wager_tier,
+                creator_amount,
+                joiner_amount,
+                platform_fee,
+                treasury_fee,
+                referral_fee,
+            });
+        } else if release_payout {
+            // Flag dismissed, pay the recorded verdict
+            match_account.status = MatchStatus::Completed;
+            
+            settle_recorded_winner(
+                config,
+                match_account,
+                &mut ctx.accounts.match_escrow,
+                &ctx.accounts.winner,
+                &ctx.accounts.treasury,
+                &ctx.accounts.referral_pool,
+                ctx.accounts.abandoner_profile.as_mut(),
+            )?;
+            
+            record_settlement(&mut ctx.accounts.config, total_pot);
+        } else {
+            // Verdict voided, return both wagers
+            let wager_amount = match_account.wager_amount;
+            require!(ctx.accounts.match_escrow.balance >= total_pot, PV3Error::InsufficientEscrowBalance);
+            
+            return_to_source(
+                &mut ctx.accounts.match_escrow,
+                match_account.creator_funding,
+                &ctx.accounts.creator,
+                ctx.accounts.creator_session_vault.as_mut(),
+                wager_amount,
+            )?;
+            
+            return_to_source(
+                &mut ctx.accounts.match_escrow,
+                match_account.joiner_funding,
+                &ctx.accounts.joiner,
+                ctx.accounts.joiner_session_vault.as_mut(),
+                wager_amount,
+            )?;
+            
+            match_account.status = MatchStatus::Refunded;
+            
+            emit!(MatchRefunded {
+                match_id: match_account.key(),
+                refund_amount: total_pot,
+            });
+        }
+        
+        emit!(FlaggedMatchResolved {
             match_id: match_account.key(),
-            refund_amount: if match_account.joiner != Pubkey::default() { wager_amount * 2 } else { wager_amount },
+            payout_released: release_payout,
+            admin1: ctx.accounts.admin1.key(),
+            admin2: ctx.accounts.admin2.key(),
+            timestamp: Clock::get()?.unix_timestamp,
         });
         
         Ok(())
     }
 
-    // ============= SESSION MANAGEMENT =============
-    
-    pub fn create_session(ctx: Context<CreateSession>) -> Result<()> {
-        session::create_session(ctx)
-    }
-
-    pub fn deposit_to_session(ctx: Context<DepositToSession>, amount: u64) -> Result<()> {
-        session::deposit_to_session(ctx, amount)
+    pub fn forfeit_match(ctx: Context<ForfeitMatch>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let match_account = &mut ctx.accounts.match_account;
+        let player = &ctx.accounts.player;
+        
+        require_unsettled(&match_account.status)?;
+        require!(
+            match_account.status == MatchStatus::InProgress,
+            PV3Error::MatchNotInProgress
+        );
+        
+        // The forfeiting player concedes, so no verifier signature is needed
+        let winner_pubkey = if player.key() == match_account.creator {
+            match_account.joiner
+        } else {
+            match_account.creator
+        };
+        require_keys_eq!(
+            ctx.accounts.opponent.key(),
+            winner_pubkey,
+            PV3Error::InvalidParticipant
+        );
+        
+        match_account.winner = winner_pubkey;
+        match_account.status = MatchStatus::Completed;
+        
+        let total_pot = match_account.total_pot;
+        let (winner_amount, platform_fee, treasury_fee, referral_fee) = distribute_winnings(
+            config,
+            total_pot,
+            &mut ctx.accounts.match_escrow,
+            &ctx.accounts.opponent,
+            &ctx.accounts.treasury,
+            &ctx.accounts.referral_pool,
+        )?;
+        
+        record_settlement(&mut ctx.accounts.config, total_pot);
+        
+        emit!(MatchForfeited {
+            match_id: match_account.key(),
+            wager_tier: match_account.wager_tier,
+            forfeited_by: player.key(),
+            winner: winner_pubkey,
+            winner_amount,
+            platform_fee,
+            treasury_fee,
+            referral_fee,
+        });
+        
+        Ok(())
     }
 
-    pub fn withdraw_from_session(ctx: Context<WithdrawFromSession>, amount: u64) -> Result<()> {
-        session::withdraw_from_session(ctx, amount)
+    pub fn abort_match(ctx: Context<AbortMatch>) -> Result<()> {
+        let match_account = &mut ctx.accounts.match_account;
+        
+        require_unsettled(&match_account.status)?;
+        require!(
+            match_account.status == MatchStatus::InProgress,
+            PV3Error::MatchNotInProgress
+        );
+        
+        let wager_amount = match_account.wager_amount;
+        require!(ctx.accounts.match_escrow.balance >= wager_amount * 2, PV3Error::InsufficientEscrowBalance);
+        
+        // Both players signed, so refund both wagers in full with no fees
+        return_to_source(
+            &mut ctx.accounts.match_escrow,
+            match_account.creator_funding,
+            &ctx.accounts.creator,
+            ctx.accounts.creator_session_vault.as_mut(),
+            wager_amount,
+        )?;
+        
+        return_to_source(
+            &mut ctx.accounts.match_escrow,
+            match_account.joiner_funding,
+            &ctx.accounts.joiner,
+            ctx.accounts.joiner_session_vault.as_mut(),
+            wager_amount,
+        )?;
+        
+        match_account.status = MatchStatus::Aborted;
+        
+        emit!(MatchAborted {
+            match_id: match_account.key(),
+            creator: ctx.accounts.creator.key(),
+            joiner: ctx.accounts.joiner.key(),
+            refund_amount: wager_amount,
+        });
+        
+        Ok(())
     }
 
-    // ============= ADMIN FUNCTIONS =============
+    pub fn submit_draw(
+        ctx: Context<SubmitDraw>,
+        result_hash: [u8; 32],
+        ed25519_signature: [u8; 64],
+        ed25519_instruction_index: u8,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let match_account = &mut ctx.accounts.match_account;
+        
+        require_unsettled(&match_account.status)?;
+        require!(
+            match_account.status == MatchStatus::InProgress,
+            PV3Error::MatchNotInProgress
+        );
+        
+        require!(!match_account.is_async, PV3Error::ScoreResultRequired);
+        
+        // Draw verdicts are signed over a distinct message so a win signature
+        // can never be replayed as a draw (or vice versa)
+        let expected_message = create_draw_message(match_account.key(), result_hash);
+        
+        verify_verifier_signature(
+            &ctx.accounts.instruction_sysvar,
+            ed25519_instruction_index,
+            &config.verifier_pubkey,
+            &ed25519_signature,
+            &expected_message,
+        )?;
+        
+        match_account.result_hash = result_hash;
+        
+        // Draws wait out the challenge period too. The pending match keeps no winner,
+        // which is how finalize_draw tells it apart from a pending win
+        if match_account.hold_for_challenge(config)? {
+            emit!(MatchResultPending {
+                match_id: match_account.key(),
+                winner: Pubkey::default(),
+                settle_after: match_account.settle_after,
+            });
+            
+            return Ok(());
+        }
+        
+        match_account.status = MatchStatus::Drawn;
+        
+        let total_pot = match_account.total_pot;
+        let (creator_amount, joiner_amount, platform_fee, treasury_fee, referral_fee) = distribute_draw(
+            config,
+            match_account,
+            &mut ctx.accounts.match_escrow,
+            &ctx.accounts.creator,
+            ctx.accounts.creator_session_vault.as_mut(),
+            &ctx.accounts.joiner,
+            ctx.accounts.joiner_session_vault.as_mut(),
+            &ctx.accounts.treasury,
+            &ctx.accounts.referral_pool,
+        )?;
+        
+        record_settlement(&mut ctx.accounts.config, total_pot);
+        
+        emit!(MatchDrawn {
+            match_id: match_account.key(),
+            wager_tier: match_account.wager_tier,
+            creator_amount,
+            joiner_amount,
+            platform_fee,
+            treasury_fee,
+            referral_fee,
+        });
+        
+        Ok(())
+    }
+
+    pub fn submit_abandonment(
+        ctx: Context<SubmitAbandonment>,
+        abandoner: Pubkey,
+        result_hash: [u8; 32],
+        ed25519_signature: [u8; 64],
+        ed25519_instruction_index: u8,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let match_account = &mut ctx.accounts.match_account;
+        
+        require_unsettled(&match_account.status)?;
+        require!(
+            match_account.status == MatchStatus::InProgress,
+            PV3Error::MatchNotInProgress
+        );
+        
+        // The remaining player takes the pot, so a quitter can no longer wait out the deadline
+        let winner_pubkey = if abandoner == match_account.creator {
+            match_account.joiner
+        } else if abandoner == match_account.joiner {
+            match_account.creator
+        } else {
+            return err!(PV3Error::InvalidParticipant);
+        };
+        require_keys_eq!(ctx.accounts.winner.key(), winner_pubkey, PV3Error::InvalidWinner);
+        
+        // Abandonment verdicts carry their own tag so they can't be replayed as a win
+        let expected_message = create_abandonment_message(
+            match_account.key(),
+            abandoner,
+            result_hash,
+        );
+        
+        verify_verifier_signature(
+            &ctx.accounts.instruction_sysvar,
+            ed25519_instruction_index,
+            &config.verifier_pubkey,
+            &ed25519_signature,
+            &expected_message,
+        )?;
+        
+        match_account.winner = winner_pubkey;
+        match_account.result_hash = result_hash;
+        match_account.abandoned_by = abandoner;
+        
+        // Joiners may never have created a profile, so the verdict opens one for them
+        let abandoner_profile = &mut ctx.accounts.abandoner_profile;
+        if abandoner_profile.owner == Pubkey::default() {
+            abandoner_profile.owner = abandoner;
+            abandoner_profile.match_nonce = 0;
+            abandoner_profile.created_at = Clock::get()?.unix_timestamp;
+            abandoner_profile.bump = ctx.bumps.abandoner_profile;
+        }
+        
+        // With a challenge period configured, hold the payout until finalize_match
+        if match_account.hold_for_challenge(config)? {
+            emit!(MatchResultPending {
+                match_id: match_account.key(),
+                winner: winner_pubkey,
+                settle_after: match_account.settle_after,
+            });
+            
+            return Ok(());
+        }
+        
+        match_account.status = MatchStatus::Completed;
+        
+        let total_pot = match_account.total_pot;
+        settle_recorded_winner(
+            config,
+            match_account,
+            &mut ctx.accounts.match_escrow,
+            &ctx.accounts.winner,
+            &ctx.accounts.treasury,
+            &ctx.accounts.referral_pool,
+            Some(&mut ctx.accounts.abandoner_profile),
+        )?;
+        
+        record_settlement(&mut ctx.accounts.config, total_pot);
+        
+        Ok(())
+    }
+
+    pub fn submit_creator_score(
+        ctx: Context<SubmitCreatorScore>,
+        score: u64,
+        result_hash: [u8; 32],
+        ed25519_signature: [u8; 64],
+        ed25519_instruction_index: u8,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let match_account = &mut ctx.accounts.match_account;
+        
+        require!(match_account.is_async, PV3Error::NotAnAsyncMatch);
+        require!(
+            match_account.status == MatchStatus::WaitingForPlayer,
+            PV3Error::MatchNotAvailable
+        );
+        require!(match_account.creator_score.is_none(), PV3Error::ScoreAlreadySubmitted);
+        // The creator's phase ends at the join deadline
+        require!(
+            Clock::get()?.unix_timestamp < match_account.join_deadline,
+            PV3Error::MatchExpired
+        );
+        
+        let expected_message = create_score_message(
+            match_account.key(),
+            match_account.creator,
+            score,
+            result_hash,
+        );
+        
+        verify_verifier_signature(
+            &ctx.accounts.instruction_sysvar,
+            ed25519_instruction_index,
+            &config.verifier_pubkey,
+            &ed25519_signature,
+            &expected_message,
+        )?;
+        
+        match_account.creator_score = Some(score);
+        
+        emit!(ScoreSubmitted {
+            match_id: match_account.key(),
+            player: match_account.creator,
+            score,
+            result_hash,
+        });
+        
+        Ok(())
+    }
+
+    pub fn submit_joiner_score(
+        ctx: Context<SubmitJoinerScore>,
+        score: u64,
+        result_hash: [u8; 32],
+        ed25519_signature: [u8; 64],
+        ed25519_instruction_index: u8,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let match_account = &mut ctx.accounts.match_account;
+        
+        require_unsettled(&match_account.status)?;
+        require!(match_account.is_async, PV3Error::NotAnAsyncMatch);
+        require!(
+            match_account.status == MatchStatus::InProgress,
+            PV3Error::MatchNotInProgress
+        );
+        require!(match_account.joiner_score.is_none(), PV3Error::ScoreAlreadySubmitted);
+        // The joiner's phase ends at the result deadline, after which they forfeit
+        require!(
+            Clock::get()?.unix_timestamp <= match_account.result_deadline,
+            PV3Error::ScoreDeadlinePassed
+        );
+        let creator_score = match_account.creator_score.ok_or(PV3Error::AwaitingCreatorScore)?;
+        
+        let expected_message = create_score_message(
+            match_account.key(),
+            match_account.joiner,
+            score,
+            result_hash,
+        );
+        
+        verify_verifier_signature(
+            &ctx.accounts.instruction_sysvar,
+            ed25519_instruction_index,
+            &config.verifier_pubkey,
+            &ed25519_signature,
+            &expected_message,
+        )?;
+        
+        match_account.joiner_score = Some(score);
+        match_account.result_hash = result_hash;
+        
+        emit!(ScoreSubmitted {
+            match_id: match_account.key(),
+            player: match_account.joiner,
+            score,
+            result_hash,
+        });
+        
+        let total_pot = match_account.total_pot;
+        
+        // Ties split the pot under the draw fee policy
+        if score == creator_score {
+            // A tie is held like a draw verdict, for finalize_draw to split
+            if match_account.hold_for_challenge(config)? {
+                emit!(MatchResultPending {
+                    match_id: match_account.key(),
+                    winner: Pubkey::default(),
+                    settle_after: match_account.settle_after,
+                });
+                
+                return Ok(());
+            }
+            
+            match_account.status = MatchStatus::Drawn;
+            
+            let (creator_amount, joiner_amount, platform_fee, treasury_fee, referral_fee) = distribute_draw(
+                config,
+                match_account,
+                &mut ctx.accounts.match_escrow,
+                &ctx.accounts.creator,
+                ctx.accounts.creator_session_vault.as_mut(),
+                &ctx.accounts.joiner,
+                ctx.accounts.joiner_session_vault.as_mut(),
+                &ctx.accounts.treasury,
+                &ctx.accounts.referral_pool,
+            )?;
+            
+            record_settlement(&mut ctx.accounts.config, total_pot);
+            
+            emit!(MatchDrawn {
+                match_id: match_account.key(),
+                wager_tier: match_account.wager_tier,
+                creator_amount,
+                joiner_amount,
+                platform_fee,
+                treasury_fee,
+                referral_fee,
+            });
+            
+            return Ok(());
+        }
+        
+        let (winner_pubkey, winner_account) = if score > creator_score {
+            (match_account.joiner, &ctx.accounts.joiner)
+        } else {
+            (match_account.creator, &ctx.accounts.creator)
+        };
+        match_account.winner = winner_pubkey;
+        
+        // Score verdicts go through the same challenge window as win verdicts
+        if match_account.hold_for_challenge(config)? {
+            emit!(MatchResultPending {
+                match_id: match_account.key(),
+                winner: winner_pubkey,
+                settle_after: match_account.settle_after,
+            });
+            
+            return Ok(());
+        }
+        
+        match_account.status = MatchStatus::Completed;
+        
+        let (winner_amount, platform_fee, treasury_fee, referral_fee) = distribute_winnings(
+            config,
+            total_pot,
+            &mut ctx.accounts.match_escrow,
+            winner_account,
+            &ctx.accounts.treasury,
+            &ctx.accounts.referral_pool,
+        )?;
+        
+        record_settlement(&mut ctx.accounts.config, total_pot);
+        
+        emit!(MatchCompleted {
+            match_id: match_account.key(),
+            wager_tier: match_account.wager_tier,
+            winner: winner_pubkey,
+            winner_amount,
+            platform_fee,
+            treasury_fee,
+            referral_fee,
+        });
+        
+        Ok(())
+    }
+
+    pub fn claim_score_timeout(ctx: Context<ClaimScoreTimeout>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let match_account = &mut ctx.accounts.match_account;
+        
+        require_unsettled(&match_account.status)?;
+        require!(match_account.is_async, PV3Error::NotAnAsyncMatch);
+        require!(
+            match_account.status == MatchStatus::InProgress,
+            PV3Error::MatchNotInProgress
+        );
+        require!(
+            Clock::get()?.unix_timestamp > match_account.result_deadline,
+            PV3Error::ResultDeadlineNotReached
+        );
+        
+        // A joiner who never posted a score forfeits to the creator's standing score
+        let winner_pubkey = match_account.creator;
+        match_account.winner = winner_pubkey;
+        match_account.status = MatchStatus::Completed;
+        
+        let total_pot = match_account.total_pot;
+        let (winner_amount, platform_fee, treasury_fee, referral_fee) = distribute_winnings(
+            config,
+            total_pot,
+            &mut ctx.accounts.match_escrow,
+            &ctx.accounts.creator,
+            &ctx.accounts.treasury,
+            &ctx.accounts.referral_pool,
+        )?;
+        
+        record_settlement(&mut ctx.accounts.config, total_pot);
+        
+        emit!(MatchForfeited {
+            match_id: match_account.key(),
+            wager_tier: match_account.wager_tier,
+            forfeited_by: match_account.joiner,
+            winner: winner_pubkey,
+            winner_amount,
+            platform_fee,
+            treasury_fee,
+            referral_fee,
+        });
+        
+        Ok(())
+    }
+
+    pub fn refund_match(ctx: Context<RefundMatch>) -> Result<()> {
+        let match_account = &mut ctx.accounts.match_account;
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let has_joiner = check_refund_expired(match_account, current_time)?;
+        let expired_at = refund_deadline(match_account);
+        if match_account.status == MatchStatus::AwaitingReady {
+            record_ready_no_shows(match_account);
+        }
+        
+        let wager_amount = match_account.wager_amount;
+        let refund_amount = if has_joiner { wager_amount * 2 } else { wager_amount };
+        require!(ctx.accounts.match_escrow.balance >= refund_amount, PV3Error::InsufficientEscrowBalance);
+        
+        // Refund creator
+        return_to_source(
+            &mut ctx.accounts.match_escrow,
+            match_account.creator_funding,
+            &ctx.accounts.creator,
+            ctx.accounts.creator_session_vault.as_mut(),
+            wager_amount,
+        )?;
+        
+        // Refund joiner if they joined
+        if has_joiner {
+            require_keys_eq!(
+                ctx.accounts.joiner.key(),
+                match_account.joiner,
+                PV3Error::InvalidParticipant
+            );
+            return_to_source(
+                &mut ctx.accounts.match_escrow,
+                match_account.joiner_funding,
+                &ctx.accounts.joiner,
+                ctx.accounts.joiner_session_vault.as_mut(),
+                wager_amount,
+            )?;
+        }
+        
+        match_account.status = MatchStatus::Refunded;
+        
+        emit!(MatchRefunded {
+            match_id: match_account.key(),
+            refund_amount,
+        });
+        
+        // Anyone may crank an expired match; the funds above only ever go to the players,
+        // and the caller is paid a bounty for the cleanup while the budget lasts
+        let keeper_bounty = pay_crank_bounty(
+            &mut ctx.accounts.config,
+            &ctx.accounts.keeper,
+            match_account,
+            expired_at,
+            current_time,
+        )?;
+        
+        emit!(ExpiredMatchCranked {
+            match_id: match_account.key(),
+            keeper: ctx.accounts.keeper.key(),
+            keeper_bounty,
+            remaining_budget: ctx.accounts.config.crank_budget,
+        });
+        
+        Ok(())
+    }
+
+    pub fn cancel_match(ctx: Context<CancelMatch>) -> Result<()> {
+        let match_account = &mut ctx.accounts.match_account;
+        
+        require_unsettled(&match_account.status)?;
+        require!(
+            match_account.status == MatchStatus::WaitingForPlayer,
+            PV3Error::CancelNotAllowed
+        );
+        
+        let refund_amount = match_account.wager_amount;
+        require!(ctx.accounts.match_escrow.balance >= refund_amount, PV3Error::InsufficientEscrowBalance);
+        
+        // Return the creator's wager immediately, no need to wait for expiry
+        return_to_source(
+            &mut ctx.accounts.match_escrow,
+            match_account.creator_funding,
+            &ctx.accounts.creator,
+            ctx.accounts.creator_session_vault.as_mut(),
+            refund_amount,
+        )?;
+        
+        match_account.status = MatchStatus::Cancelled;
+        
+        emit!(MatchCancelled {
+            match_id: match_account.key(),
+            creator: ctx.accounts.creator.key(),
+            refund_amount,
+        });
+        
+        Ok(())
+    }
+
+    pub fn index_match(ctx: Context<IndexMatch>) -> Result<()> {
+        let match_account = &ctx.accounts.match_account;
+        
+        init_match_index(
+            &mut ctx.accounts.match_index,
+            match_account.match_number,
+            match_account.key(),
+            ctx.bumps.match_index,
+        );
+        
+        emit!(MatchIndexed {
+            match_number: match_account.match_number,
+            match_account: match_account.key(),
+        });
+        
+        Ok(())
+    }
+
+    pub fn close_match(ctx: Context<CloseMatch>) -> Result<()> {
+        let match_account = &ctx.accounts.match_account;
+        
+        require_closable(&match_account.status)?;
+        
+        // Both accounts are closed to the creator, who paid for them, by their `close`
+        // constraints. The escrow hands back its rent reserve plus any rounding dust
+        let escrow_swept = ctx.accounts.match_escrow.to_account_info().lamports();
+        
+        close_if_initialized(&ctx.accounts.match_index, &ctx.accounts.creator)?;
+        
+        // A dispute's rent goes back to the player who opened it
+        let dispute = &ctx.accounts.dispute;
+        if dispute.owner == &crate::ID {
+            let opened_by = Dispute::try_deserialize(&mut &dispute.try_borrow_data()?[..])?.opened_by;
+            let dispute_opener = ctx.accounts.dispute_opener
+                .as_ref()
+                .ok_or(PV3Error::DisputeOpenerMismatch)?;
+            require_keys_eq!(dispute_opener.key(), opened_by, PV3Error::DisputeOpenerMismatch);
+            close_if_initialized(dispute, dispute_opener)?;
+        }
+        
+        emit!(MatchClosed {
+            match_id: match_account.key(),
+            creator: ctx.accounts.creator.key(),
+            rent_reclaimed: match_account.to_account_info().lamports(),
+            escrow_swept,
+        });
+        
+        Ok(())
+    }
+
+    // ============= PROFILE MANAGEMENT =============
+    
+    pub fn create_profile(ctx: Context<CreateProfile>) -> Result<()> {
+        profile::create_profile(ctx)
+    }
+
+    // ============= SESSION MANAGEMENT =============
+    
+    pub fn create_session(ctx: Context<CreateSession>) -> Result<()> {
+        session::create_session(ctx)
+    }
+
+    pub fn deposit_to_session(ctx: Context<DepositToSession>, amount: u64) -> Result<()> {
+        session::deposit_to_session(ctx, amount)
+    }
+
+    pub fn withdraw_from_session(ctx: Context<WithdrawFromSession>, amount: u64) -> Result<()> {
+        session::withdraw_from_session(ctx, amount)
+    }
+
+    // ============= DISPUTES =============
+    
+    pub fn open_dispute(ctx: Context<OpenDispute>, evidence_hash: [u8; 32]) -> Result<()> {
+        dispute::open_dispute(ctx, evidence_hash)
+    }
+    
+    pub fn resolve_dispute(
+        ctx: Context<ResolveDispute>,
+        resolution: DisputeResolution,
+    ) -> Result<()> {
+        dispute::resolve_dispute(ctx, resolution)
+    }
+
+    // ============= LOBBIES =============
+    
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_lobby(
+        ctx: Context<CreateLobby>,
+        nonce: u64,
+        game_id: String,
+        wager_amount: u64,
+        max_players: u8,
+        payout_bps: Vec<u16>,
+        join_deadline: i64,
+        result_window: i64,
+        use_session_vault: bool,
+    ) -> Result<()> {
+        lobby::create_lobby(
+            ctx,
+            nonce,
+            game_id,
+            wager_amount,
+            max_players,
+            payout_bps,
+            join_deadline,
+            result_window,
+            use_session_vault,
+        )
+    }
+    
+    pub fn index_lobby(ctx: Context<IndexLobby>) -> Result<()> {
+        lobby::index_lobby(ctx)
+    }
+    
+    pub fn join_lobby(ctx: Context<JoinLobby>, use_session_vault: bool) -> Result<()> {
+        lobby::join_lobby(ctx, use_session_vault)
+    }
+    
+    pub fn start_lobby(ctx: Context<StartLobby>) -> Result<()> {
+        lobby::start_lobby(ctx)
+    }
+    
+    pub fn submit_lobby_result<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SubmitLobbyResult<'info>>,
+        ranking: Vec<Pubkey>,
+        result_hash: [u8; 32],
+        ed25519_signature: [u8; 64],
+        ed25519_instruction_index: u8,
+    ) -> Result<()> {
+        lobby::submit_lobby_result(
+            ctx,
+            ranking,
+            result_hash,
+            ed25519_signature,
+            ed25519_instruction_index,
+        )
+    }
+    
+    pub fn finalize_lobby<'info>(ctx: Context<'_, '_, 'info, 'info, FinalizeLobby<'info>>) -> Result<()> {
+        lobby::finalize_lobby(ctx)
+    }
+    
+    pub fn flag_lobby(ctx: Context<FlagLobby>) -> Result<()> {
+        lobby::flag_lobby(ctx)
+    }
+    
+    pub fn resolve_flagged_lobby<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ResolveFlaggedLobby<'info>>,
+        release_payout: bool,
+    ) -> Result<()> {
+        lobby::resolve_flagged_lobby(ctx, release_payout)
+    }
+    
+    pub fn refund_lobby<'info>(ctx: Context<'_, '_, 'info, 'info, RefundLobby<'info>>) -> Result<()> {
+        lobby::refund_lobby(ctx)
+    }
+    
+    pub fn close_lobby(ctx: Context<CloseLobby>) -> Result<()> {
+        lobby::close_lobby(ctx)
+    }
+
+    // ============= TEAM MATCHES =============
+    
+    pub fn create_team_match(
+        ctx: Context<CreateTeamMatch>,
+        nonce: u64,
+        game_id: String,
+        wager_amount: u64,
+        join_deadline: i64,
+        result_window: i64,
+        use_session_vault: bool,
+    ) -> Result<()> {
+        team::create_team_match(
+            ctx,
+            nonce,
+            game_id,
+            wager_amount,
+            join_deadline,
+            result_window,
+            use_session_vault,
+        )
+    }
+    
+    pub fn index_team_match(ctx: Context<IndexTeamMatch>) -> Result<()> {
+        team::index_team_match(ctx)
+    }
+    
+    pub fn join_team_match(
+        ctx: Context<JoinTeamMatch>,
+        side: TeamSide,
+        use_session_vault: bool,
+    ) -> Result<()> {
+        team::join_team_match(ctx, side, use_session_vault)
+    }
+    
+    pub fn submit_team_result<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SubmitTeamResult<'info>>,
+        winning_team: TeamSide,
+        result_hash: [u8; 32],
+        ed25519_signature: [u8; 64],
+        ed25519_instruction_index: u8,
+    ) -> Result<()> {
+        team::submit_team_result(
+            ctx,
+            winning_team,
+            result_hash,
+            ed25519_signature,
+            ed25519_instruction_index,
+        )
+    }
+    
+    pub fn finalize_team_match<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FinalizeTeamMatch<'info>>,
+    ) -> Result<()> {
+        team::finalize_team_match(ctx)
+    }
+    
+    pub fn flag_team_match(ctx: Context<FlagTeamMatch>) -> Result<()> {
+        team::flag_team_match(ctx)
+    }
+    
+    pub fn resolve_flagged_team_match<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ResolveFlaggedTeamMatch<'info>>,
+        release_payout: bool,
+    ) -> Result<()> {
+        team::resolve_flagged_team_match(ctx, release_payout)
+    }
+    
+    pub fn refund_team_match<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefundTeamMatch<'info>>,
+    ) -> Result<()> {
+        team::refund_team_match(ctx)
+    }
+    
+    pub fn close_team_match(ctx: Context<CloseTeamMatch>) -> Result<()> {
+        team::close_team_match(ctx)
+    }
+
+    // ============= BATCH OPERATIONS =============
+    
+    pub fn batch_refund_matches<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchRefundMatches<'info>>,
+    ) -> Result<()> {
+        batch::batch_refund_matches(ctx)
+    }
+    
+    pub fn batch_submit_results<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchSubmitResults<'info>>,
+        results: Vec<BatchResult>,
+    ) -> Result<()> {
+        batch::batch_submit_results(ctx, results)
+    }
+
+    // ============= ADMIN FUNCTIONS =============
+    
+    pub fn emergency_pause(ctx: Context<EmergencyAction>) -> Result<()> {
+        admin::emergency_pause(ctx)
+    }
+    
+    pub fn emergency_unpause(ctx: Context<EmergencyAction>) -> Result<()> {
+        admin::emergency_unpause(ctx)
+    }
+    
+    pub fn update_fees(
+        ctx: Context<EmergencyAction>, 
+        platform_fee_bps: u16,
+        treasury_fee_bps: u16,
+        referral_fee_bps: u16,
+    ) -> Result<()> {
+        admin::update_fees(ctx, platform_fee_bps, treasury_fee_bps, referral_fee_bps)
+    }
+    
+    pub fn update_draw_fee_policy(
+        ctx: Context<EmergencyAction>,
+        draw_fee_policy: FeePolicy,
+    ) -> Result<()> {
+        admin::update_draw_fee_policy(ctx, draw_fee_policy)
+    }
+    
+    pub fn update_crank_bounty(ctx: Context<EmergencyAction>, crank_bounty: u64) -> Result<()> {
+        admin::update_crank_bounty(ctx, crank_bounty)
+    }
+    
+    pub fn fund_crank_budget(ctx: Context<FundCrankBudget>, amount: u64) -> Result<()> {
+        admin::fund_crank_budget(ctx, amount)
+    }
+    
+    pub fn update_abandonment_fee_policy(
+        ctx: Context<EmergencyAction>,
+        abandonment_fee_policy: FeePolicy,
+    ) -> Result<()> {
+        admin::update_abandonment_fee_policy(ctx, abandonment_fee_policy)
+    }
+    
+    pub fn update_challenge_period(
+        ctx: Context<EmergencyAction>,
+        challenge_period: i64,
+    ) -> Result<()> {
+        admin::update_challenge_period(ctx, challenge_period)
+    }
+    
+    pub fn update_wager_bounds(
+        ctx: Context<EmergencyAction>,
+        min_wager: u64,
+        max_wager: u64,
+    ) -> Result<()> {
+        admin::update_wager_bounds(ctx, min_wager, max_wager)
+    }
+    
+    pub fn create_game_config(
+        ctx: Context<CreateGameConfig>,
+        game_id: String,
+        min_wager: u64,
+        max_wager: u64,
+    ) -> Result<()> {
+        admin::create_game_config(ctx, game_id, min_wager, max_wager)
+    }
+    
+    pub fn update_wager_tiers(
+        ctx: Context<EmergencyAction>,
+        wager_tiers: Vec<u64>,
+    ) -> Result<()> {
+        admin::update_wager_tiers(ctx, wager_tiers)
+    }
+    
+    pub fn update_game_wager_bounds(
+        ctx: Context<UpdateGameConfig>,
+        min_wager: u64,
+        max_wager: u64,
+    ) -> Result<()> {
+        admin::update_game_wager_bounds(ctx, min_wager, max_wager)
+    }
+    
+    pub fn recover_inactive_vault(
+        ctx: Context<RecoverVault>,
+        inactivity_threshold_hours: u64,
+    ) -> Result<()> {
+        admin::recover_inactive_vault(ctx, inactivity_threshold_hours)
+    }
+}
+
+// Helper functions
+fn create_result_message(match_id: Pubkey, winner: Pubkey, result_hash: [u8; 32]) -> Vec<u8> {
+    let mut message = Vec::new();
+    message.extend_from_slice(&match_id.to_bytes());
+    message.extend_from_slice(&winner.to_bytes());
+    message.extend_from_slice(&result_hash);
+    message
+}
+
+// Per-game overrides win over the platform-wide bounds. The game config PDA is always
+// passed so creators can't skip a stricter override; it is simply empty when unset
+fn active_wager_bounds(config: &PlatformConfig, game_config: &AccountInfo) -> Result<(u64, u64)> {
+    if game_config.owner != &crate::ID || game_config.data_is_empty() {
+        return Ok((config.min_wager, config.max_wager));
+    }
+    
+    let game_config = GameConfig::try_deserialize(&mut &game_config.data.borrow()[..])?;
+    Ok((game_config.min_wager, game_config.max_wager))
+}
+
+// Checks the wager against the active bounds, reporting them on failure so clients
+// can show the player what the limits currently are
+fn require_wager_in_bounds(
+    config: &PlatformConfig,
+    game_config: &AccountInfo,
+    wager_amount: u64,
+) -> Result<()> {
+    let (min_wager, max_wager) = active_wager_bounds(config, game_config)?;
+    if !(min_wager..=max_wager).contains(&wager_amount) {
+        msg!("Wager bounds: min {}, max {}", min_wager, max_wager);
+        emit!(WagerOutOfBounds {
+            wager_amount,
+            min_wager,
+            max_wager,
+        });
+    }
+    require_gte!(wager_amount, min_wager, PV3Error::WagerTooLow);
+    require_gte!(max_wager, wager_amount, PV3Error::WagerTooHigh);
+    
+    Ok(())
+}
+
+// Position of the wager in the configured tier list. An empty list disables tiers
+fn wager_tier_index(config: &PlatformConfig, wager_amount: u64) -> Result<Option<u8>> {
+    if config.wager_tiers.is_empty() {
+        return Ok(None);
+    }
+    
+    let tier = config.wager_tiers
+        .iter()
+        .position(|tier| *tier == wager_amount)
+        .ok_or(PV3Error::WagerNotATier)?;
+    Ok(Some(tier as u8))
+}
+
+// Hands out the next global match number. Matches, lobbies and team matches share
+// one sequence, so a number alone identifies any of them
+fn next_match_number(config: &mut PlatformConfig) -> u64 {
+    let match_number = config.next_match_number;
+    config.next_match_number += 1;
+    match_number
+}
+
+fn init_match_index(match_index: &mut MatchIndex, match_number: u64, game: Pubkey, bump: u8) {
+    match_index.match_number = match_number;
+    match_index.match_account = game;
+    match_index.bump = bump;
+}
+
+// Index entries and disputes are optional, so their PDAs are always passed and only
+// closed if they were ever created
+fn close_if_initialized<'info>(
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    if account.owner != &crate::ID {
+        return Ok(());
+    }
+    
+    let rent = account.lamports();
+    **account.try_borrow_mut_lamports()? -= rent;
+    **destination.try_borrow_mut_lamports()? += rent;
+    account.assign(&System::id());
+    account.resize(0)?;
+    
+    Ok(())
+}
+
+// Matches, lobbies and team matches can only be closed once no funds remain at stake
+fn require_closable(status: &MatchStatus) -> Result<()> {
+    require!(
+        matches!(
+            status,
+            MatchStatus::Completed |
+            MatchStatus::Drawn |
+            MatchStatus::Refunded |
+            MatchStatus::Cancelled |
+            MatchStatus::Aborted
+        ),
+        PV3Error::MatchNotSettled
+    );
+    Ok(())
+}
+
+// Moves a player's wager into the match escrow, either out of their session vault
+// balance or as a direct SOL transfer from their wallet
+fn fund_wager<'info>(
+    use_session_vault: bool,
+    wager_amount: u64,
+    session_vault: Option<&mut Account<'info, SessionVault>>,
+    player: &Signer<'info>,
+    match_escrow: &mut Account<'info, Escrow>,
+    system_program: &Program<'info, System>,
+) -> Result<FundingSource> {
+    if use_session_vault {
+        // Use funds from the player's session vault
+        let session_vault = session_vault.ok_or(PV3Error::SessionVaultRequired)?;
+        deposit_from_session(match_escrow, session_vault, wager_amount)?;
+        
+        session_vault.matches_played += 1;
+        session_vault.last_activity = Clock::get()?.unix_timestamp;
+        
+        Ok(FundingSource::SessionVault)
+    } else {
+        // Direct SOL transfer from the player
+        deposit_to_escrow(match_escrow, player, system_program, wager_amount)?;
+        
+        Ok(FundingSource::Wallet)
+    }
+}
+
+// Records a verified winner and either pays out immediately or, with a challenge
+// period configured, holds the payout until finalize_match
+fn settle_verified_result(
+    accounts: &mut SubmitResult,
+    winner_pubkey: Pubkey,
+    result_hash: [u8; 32],
+) -> Result<()> {
+    let config = &accounts.config;
+    let match_account = &mut accounts.match_account;
+    
+    // Update match with verified result
+    match_account.winner = winner_pubkey;
+    match_account.result_hash = result_hash;
+    
+    // With a challenge period configured, hold the payout until finalize_match
+    if match_account.hold_for_challenge(config)? {
+        emit!(MatchResultPending {
+            match_id: match_account.key(),
+            winner: winner_pubkey,
+            settle_after: match_account.settle_after,
+        });
+        
+        return Ok(());
+    }
+    
+    require_keys_eq!(accounts.winner.key(), winner_pubkey, PV3Error::InvalidWinner);
+    match_account.status = MatchStatus::Completed;
+    
+    let total_pot = match_account.total_pot;
+    let (winner_amount, platform_fee, treasury_fee, referral_fee) = distribute_winnings(
+        config,
+        total_pot,
+        &mut accounts.match_escrow,
+        &accounts.winner,
+        &accounts.treasury,
+        &accounts.referral_pool,
+    )?;
+    
+    // Update global stats
+    record_settlement(&mut accounts.config, total_pot);
+    
+    emit!(MatchCompleted {
+        match_id: match_account.key(),
+        wager_tier: match_account.wager_tier,
+        winner: winner_pubkey,
+        winner_amount,
+        platform_fee,
+        treasury_fee,
+        referral_fee,
+    });
+    
+    Ok(())
+}
+
+// Pays the keeper who cranked an expired match out of the config's bounty budget.
+// Returns 0 instead of failing once the budget runs dry, so refunds never depend on it.
+// Players reclaiming their own match earn nothing, and keepers only once the match
+// has sat expired for CRANK_BOUNTY_DELAY
+fn pay_crank_bounty(
+    config: &mut Account<PlatformConfig>,
+    keeper: &AccountInfo,
+    match_account: &Match,
+    expired_at: i64,
+    current_time: i64,
+) -> Result<u64> {
+    let bounty = config.crank_bounty;
+    if bounty == 0 || config.crank_budget < bounty {
+        return Ok(0);
+    }
+    if keeper.key() == match_account.creator
+        || keeper.key() == match_account.joiner
+        || current_time < expired_at + CRANK_BOUNTY_DELAY
+    {
+        return Ok(0);
+    }
+    
+    // The budget sits in the config account on top of its rent reserve
+    **config.to_account_info().try_borrow_mut_lamports()? -= bounty;
+    **keeper.try_borrow_mut_lamports()? += bounty;
+    config.crank_budget -= bounty;
+    
+    Ok(bounty)
+}
+
+// Checks that an unsettled match has passed the deadline for its current stage.
+// Returns whether a joiner's wager is also held and must be refunded
+fn check_refund_expired(match_account: &Match, current_time: i64) -> Result<bool> {
+    match match_account.status {
+        // A player missed the ready check, so the game never started
+        MatchStatus::AwaitingReady => {
+            require!(
+                current_time > match_account.ready_deadline,
+                PV3Error::ReadyDeadlineNotReached
+            );
+            Ok(true)
+        }
+        // Async joiners who never play forfeit through claim_score_timeout
+        MatchStatus::InProgress if match_account.is_async => err!(PV3Error::RefundNotAllowed),
+        _ => {
+            require_refund_expired(
+                &match_account.status,
+                match_account.join_deadline,
+                match_account.result_deadline,
+                current_time,
+            )?;
+            Ok(match_account.status == MatchStatus::InProgress)
+        }
+    }
+}
+
+// The deadline an expired match is being refunded after, read before it is marked refunded
+fn refund_deadline(match_account: &Match) -> i64 {
+    match match_account.status {
+        MatchStatus::AwaitingReady => match_account.ready_deadline,
+        MatchStatus::InProgress => match_account.result_deadline,
+        _ => match_account.join_deadline,
+    }
+}
+
+// Flags whoever missed the ready check before an expired AwaitingReady match is refunded
+fn record_ready_no_shows(match_account: &mut Account<Match>) {
+    match_account.creator_no_show = !match_account.creator_ready;
+    match_account.joiner_no_show = !match_account.joiner_ready;
+    
+    emit!(ReadyCheckFailed {
+        match_id: match_account.key(),
+        creator_no_show: match_account.creator_no_show,
+        joiner_no_show: match_account.joiner_no_show,
+    });
+}
+
+// Rejects any attempt to settle or refund a match that already reached a terminal state
+fn require_unsettled(status: &MatchStatus) -> Result<()> {
+    match status {
+        MatchStatus::Completed | MatchStatus::Drawn => err!(PV3Error::MatchAlreadySettled),
+        MatchStatus::Refunded => err!(PV3Error::MatchAlreadyRefunded),
+        MatchStatus::Cancelled => err!(PV3Error::MatchAlreadyCancelled),
+        MatchStatus::Aborted => err!(PV3Error::MatchAlreadyAborted),
+        _ => Ok(()),
+    }
+}
+
+// Holds a verdict for the configured challenge period. Returns false when there is
+// none, in which case the caller pays out straight away
+fn hold_for_challenge(
+    config: &PlatformConfig,
+    status: &mut MatchStatus,
+    settle_after: &mut i64,
+) -> Result<bool> {
+    if config.challenge_period == 0 {
+        return Ok(false);
+    }
+    
+    *status = MatchStatus::PendingSettlement;
+    *settle_after = Clock::get()?.unix_timestamp + config.challenge_period;
+    Ok(true)
+}
+
+// A held verdict pays out once its challenge period is over, unless it was flagged
+fn require_finalizable(status: &MatchStatus, settle_after: i64) -> Result<()> {
+    require_unsettled(status)?;
+    require!(*status != MatchStatus::Flagged, PV3Error::MatchFlagged);
+    require!(
+        *status == MatchStatus::PendingSettlement,
+        PV3Error::MatchNotPendingSettlement
+    );
+    require!(
+        Clock::get()?.unix_timestamp >= settle_after,
+        PV3Error::ChallengePeriodActive
+    );
+    Ok(())
+}
+
+// A held verdict can only be flagged while its challenge period runs
+fn require_flaggable(status: &MatchStatus, settle_after: i64, current_time: i64) -> Result<()> {
+    require!(
+        *status == MatchStatus::PendingSettlement,
+        PV3Error::MatchNotPendingSettlement
+    );
+    require!(current_time < settle_after, PV3Error::ChallengePeriodEnded);
+    Ok(())
+}
+
+// Checks that nobody joined before the join deadline, or that no result arrived
+// before the result deadline once the game started
+fn require_refund_expired(
+    status: &MatchStatus,
+    join_deadline: i64,
+    result_deadline: i64,
+    current_time: i64,
+) -> Result<()> {
+    require_unsettled(status)?;
+    
+    match status {
+        MatchStatus::WaitingForPlayer => require!(
+            current_time > join_deadline,
+            PV3Error::JoinDeadlineNotReached
+        ),
+        MatchStatus::InProgress => require!(
+            current_time > result_deadline,
+            PV3Error::ResultDeadlineNotReached
+        ),
+        _ => return err!(PV3Error::RefundNotAllowed),
+    }
+    Ok(())
+}
+
+// Counts a settled match and its pot in the platform stats
+fn record_settlement(config: &mut PlatformConfig, total_pot: u64) {
+    config.total_matches += 1;
+    config.total_volume += total_pot;
+}
+
+// Per-game series verdicts are tagged and numbered so each signature covers one game
+fn create_game_result_message(
+    match_id: Pubkey,
+    game_number: u8,
+    winner: Pubkey,
+    result_hash: [u8; 32],
+) -> Vec<u8> {
+    let mut message = Vec::new();
+    message.extend_from_slice(GAME_VERDICT_TAG);
+    message.extend_from_slice(&match_id.to_bytes());
+    message.push(game_number);
+    message.extend_from_slice(&winner.to_bytes());
+    message.extend_from_slice(&result_hash);
+    message
+}
+
+// Draw verdicts carry a domain tag instead of a winner pubkey
+fn create_draw_message(match_id: Pubkey, result_hash: [u8; 32]) -> Vec<u8> {
+    let mut message = Vec::new();
+    message.extend_from_slice(DRAW_VERDICT_TAG);
+    message.extend_from_slice(&match_id.to_bytes());
+    message.extend_from_slice(&result_hash);
+    message
+}
+
+// Abandonment verdicts name the player who left rather than the winner
+fn create_abandonment_message(
+    match_id: Pubkey,
+    abandoner: Pubkey,
+    result_hash: [u8; 32],
+) -> Vec<u8> {
+    let mut message = Vec::new();
+    message.extend_from_slice(ABANDON_VERDICT_TAG);
+    message.extend_from_slice(&match_id.to_bytes());
+    message.extend_from_slice(&abandoner.to_bytes());
+    message.extend_from_slice(&result_hash);
+    message
+}
+
+// Async score verdicts bind the score to the player who earned it
+fn create_score_message(
+    match_id: Pubkey,
+    player: Pubkey,
+    score: u64,
+    result_hash: [u8; 32],
+) -> Vec<u8> {
+    let mut message = Vec::new();
+    message.extend_from_slice(SCORE_VERDICT_TAG);
+    message.extend_from_slice(&match_id.to_bytes());
+    message.extend_from_slice(&player.to_bytes());
+    message.extend_from_slice(&score.to_le_bytes());
+    message.extend_from_slice(&result_hash);
+    message
+}
+
+// Calculate fees according to whitepaper: 6.5% total (5.5% treasury, 1% referral)
+fn calculate_fees(config: &PlatformConfig, total_pot: u64) -> (u64, u64, u64) {
+    let platform_fee = (total_pot * config.platform_fee_bps as u64) / 10000;
+    let treasury_fee = (total_pot * config.treasury_fee_bps as u64) / 10000;
+    let referral_fee = (total_pot * config.referral_fee_bps as u64) / 10000;
+    (platform_fee, treasury_fee, referral_fee)
+}
+
+// Pays the winner from escrow and routes the platform fee to treasury and referral pool.
+// Returns (winner_amount, platform_fee, treasury_fee, referral_fee)
+fn distribute_winnings<'info>(
+    config: &PlatformConfig,
+    total_pot: u64,
+    match_escrow: &mut Account<'info, Escrow>,
+    winner: &AccountInfo<'info>,
+    treasury: &AccountInfo<'info>,
+    referral_pool: &AccountInfo<'info>,
+) -> Result<(u64, u64, u64, u64)> {
+    let (platform_fee, treasury_fee, referral_fee) = calculate_fees(config, total_pot);
+    let winner_amount = total_pot - platform_fee;
+    
+    // Transfer funds from escrow
+    require!(match_escrow.balance >= total_pot, PV3Error::InsufficientEscrowBalance);
+    
+    // Transfer to winner
+    release_from_escrow(match_escrow, winner, winner_amount)?;
+    release_platform_fee(match_escrow, treasury, referral_pool, treasury_fee, referral_fee)?;
+    
+    Ok((winner_amount, platform_fee, treasury_fee, referral_fee))
+}
+
+// Pays a recorded verdict and announces it. An abandonment is only counted against
+// the abandoner here, once it actually pays out
+fn settle_recorded_winner<'info>(
+    config: &PlatformConfig,
+    match_account: &Account<'info, Match>,
+    match_escrow: &mut Account<'info, Escrow>,
+    winner: &AccountInfo<'info>,
+    treasury: &AccountInfo<'info>,
+    referral_pool: &AccountInfo<'info>,
+    abandoner_profile: Option<&mut Account<'info, PlayerProfile>>,
+) -> Result<()> {
+    let (winner_amount, platform_fee, treasury_fee, referral_fee) = pay_recorded_winner(
+        config,
+        match_account,
+        match_escrow,
+        winner,
+        treasury,
+        referral_pool,
+    )?;
+    
+    if match_account.abandoned_by == Pubkey::default() {
+        emit!(MatchCompleted {
+            match_id: match_account.key(),
+            wager_tier: match_account.wager_tier,
+            winner: match_account.winner,
+            winner_amount,
+            platform_fee,
+            treasury_fee,
+            referral_fee,
+        });
+        
+        return Ok(());
+    }
+    
+    let abandoner_profile = abandoner_profile.ok_or(PV3Error::AbandonerProfileRequired)?;
+    abandoner_profile.abandonments += 1;
+    
+    emit!(MatchAbandoned {
+        match_id: match_account.key(),
+        wager_tier: match_account.wager_tier,
+        abandoned_by: match_account.abandoned_by,
+        winner: match_account.winner,
+        winner_amount,
+        platform_fee,
+        treasury_fee,
+        referral_fee,
+        abandonments: abandoner_profile.abandonments,
+    });
+    
+    Ok(())
+}
+
+// Pays a recorded verdict to its winner. Abandonment verdicts follow the abandonment
+// fee policy, everything else pays the normal fee
+fn pay_recorded_winner<'info>(
+    config: &PlatformConfig,
+    match_account: &Match,
+    match_escrow: &mut Account<'info, Escrow>,
+    winner: &AccountInfo<'info>,
+    treasury: &AccountInfo<'info>,
+    referral_pool: &AccountInfo<'info>,
+) -> Result<(u64, u64, u64, u64)> {
+    let total_pot = match_account.total_pot;
+    if match_account.abandoned_by != Pubkey::default()
+        && config.abandonment_fee_policy == FeePolicy::NoFee
+    {
+        require!(match_escrow.balance >= total_pot, PV3Error::InsufficientEscrowBalance);
+        release_from_escrow(match_escrow, winner, total_pot)?;
+        return Ok((total_pot, 0, 0, 0));
+    }
+    
+    distribute_winnings(config, total_pot, match_escrow, winner, treasury, referral_pool)
+}
+
+// Splits the pot evenly under the draw fee policy, any odd lamport goes to the creator.
+// Each share goes back to the source that funded that side's wager.
+// Returns (creator_amount, joiner_amount, platform_fee, treasury_fee, referral_fee)
+#[allow(clippy::too_many_arguments)]
+fn distribute_draw<'info>(
+    config: &PlatformConfig,
+    match_account: &Match,
+    match_escrow: &mut Account<'info, Escrow>,
+    creator: &AccountInfo<'info>,
+    creator_session_vault: Option<&mut Account<'info, SessionVault>>,
+    joiner: &AccountInfo<'info>,
+    joiner_session_vault: Option<&mut Account<'info, SessionVault>>,
+    treasury: &AccountInfo<'info>,
+    referral_pool: &AccountInfo<'info>,
+) -> Result<(u64, u64, u64, u64, u64)> {
+    let total_pot = match_account.total_pot;
+    let (platform_fee, treasury_fee, referral_fee) = match config.draw_fee_policy {
+        FeePolicy::NoFee => (0, 0, 0),
+        FeePolicy::NormalFee => calculate_fees(config, total_pot),
+    };
+    
+    let split_amount = total_pot - platform_fee;
+    let joiner_amount = split_amount / 2;
+    let creator_amount = split_amount - joiner_amount;
+    
+    require!(match_escrow.balance >= total_pot, PV3Error::InsufficientEscrowBalance);
+    
+    return_to_source(
+        match_escrow,
+        match_account.creator_funding,
+        creator,
+        creator_session_vault,
+        creator_amount,
+    )?;
+    return_to_source(
+        match_escrow,
+        match_account.joiner_funding,
+        joiner,
+        joiner_session_vault,
+        joiner_amount,
+    )?;
+    
+    release_platform_fee(match_escrow, treasury, referral_pool, treasury_fee, referral_fee)?;
+    
+    Ok((creator_amount, joiner_amount, platform_fee, treasury_fee, referral_fee))
+}
+
+// Loads the ed25519 instruction at the given relative index and checks it
+// carries the verifier's signature over the expected message
+fn verify_verifier_signature(
+    instruction_sysvar: &AccountInfo,
+    ed25519_instruction_index: u8,
+    verifier_pubkey: &Pubkey,
+    signature: &[u8; 64],
+    message: &[u8],
+) -> Result<()> {
+    let instruction = instructions::get_instruction_relative(
+        ed25519_instruction_index as i64,
+        instruction_sysvar
+    )?;
+    
+    require!(
+        instruction.program_id == ed25519_program::ID,
+        PV3Error::InvalidSignatureInstruction
+    );
+    
+    verify_ed25519_signature(
+        signature,
+        message,
+        &verifier_pubkey.to_bytes(),
+        &instruction,
+    )
+}
+
+// Ed25519 precompile data: a signature count, one byte of padding, then one
+// 14-byte offsets struct per signature
+const ED25519_OFFSETS_START: usize = 2;
+const ED25519_OFFSETS_SIZE: usize = 14;
+
+fn verify_ed25519_signature(
+    signature: &[u8; 64],
+    message: &[u8],
+    pubkey: &[u8; 32],
+    instruction: &Instruction,
+) -> Result<()> {
+    let data = &instruction.data;
+    require!(
+        data.len() >= ED25519_OFFSETS_START + ED25519_OFFSETS_SIZE,
+        PV3Error::InvalidSignatureData
+    );
+    require!(data[0] == 1, PV3Error::InvalidSignatureCount);
+    
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let offsets = ED25519_OFFSETS_START;
+    let signature_offset = read_u16(offsets) as usize;
+    let signature_instruction_index = read_u16(offsets + 2);
+    let pubkey_offset = read_u16(offsets + 4) as usize;
+    let pubkey_instruction_index = read_u16(offsets + 6);
+    let message_offset = read_u16(offsets + 8) as usize;
+    let message_size = read_u16(offsets + 10) as usize;
+    let message_instruction_index = read_u16(offsets + 12);
+    
+    // u16::MAX means "this instruction", otherwise the precompile would have
+    // verified bytes that live in some other instruction of the transaction
+    require!(
+        signature_instruction_index == u16::MAX
+            && pubkey_instruction_index == u16::MAX
+            && message_instruction_index == u16::MAX,
+        PV3Error::InvalidSignatureData
+    );
+    
+    let inst_signature = data
+        .get(signature_offset..signature_offset + 64)
+        .ok_or(PV3Error::InvalidSignatureData)?;
+    let inst_pubkey = data
+        .get(pubkey_offset..pubkey_offset + 32)
+        .ok_or(PV3Error::InvalidSignatureData)?;
+    let inst_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(PV3Error::InvalidSignatureData)?;
+    
+    require!(
+        inst_signature == signature,
+        PV3Error::SignatureMismatch
+    );
+    require!(
+        inst_pubkey == pubkey,
+        PV3Error::PublicKeyMismatch
+    );
+    require!(
+        inst_message == message,
+        PV3Error::MessageMismatch
+    );
+    
+    Ok(())
+}
+
+// Account structures
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PlatformConfig::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce: u64, game_id: String)]
+pub struct CreateMatch<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Match::INIT_SPACE,
+        seeds = [b"match", creator.key().as_ref(), &nonce.to_le_bytes()],
+        bump
+    )]
+    pub match_account: Account<'info, Match>,
+    
+    #[account(
+        mut,
+        seeds = [b"profile", creator.key().as_ref()],
+        bump = creator_profile.bump
+    )]
+    pub creator_profile: Account<'info, PlayerProfile>,
+    
+    #[account(
+        seeds = [b"game_config", hash(game_id.as_bytes()).as_ref()],
+        bump
+    )]
+    /// CHECK: Optional per-game wager bounds, may be uninitialized
+    pub game_config: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Escrow::INIT_SPACE,
+        seeds = [b"escrow", match_account.key().as_ref()],
+        bump
+    )]
+    pub match_escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        seeds = [b"session", creator.key().as_ref()],
+        bump
+    )]
+    pub session_vault: Option<Account<'info, SessionVault>>,
+    
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateRematch<'info> {
+    #[account(
+        constraint = previous_match.creator == creator.key() ||
+            previous_match.joiner == creator.key() @ PV3Error::InvalidParticipant
+    )]
+    pub previous_match: Account<'info, Match>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Match::INIT_SPACE,
+        seeds = [b"rematch", previous_match.key().as_ref()],
+        bump
+    )]
+    pub match_account: Account<'info, Match>,
+    
+    #[account(
+        seeds = [b"game_config", hash(previous_match.game_id.as_bytes()).as_ref()],
+        bump
+    )]
+    /// CHECK: Optional per-game wager bounds, may be uninitialized
+    pub game_config: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Escrow::INIT_SPACE,
+        seeds = [b"escrow", match_account.key().as_ref()],
+        bump
+    )]
+    pub match_escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        seeds = [b"session", creator.key().as_ref()],
+        bump
+    )]
+    pub session_vault: Option<Account<'info, SessionVault>>,
+    
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinMatch<'info> {
+    #[account(mut)]
+    pub match_account: Account<'info, Match>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", match_account.key().as_ref()],
+        bump = match_escrow.bump
+    )]
+    pub match_escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        seeds = [b"session", joiner.key().as_ref()],
+        bump
+    )]
+    pub session_vault: Option<Account<'info, SessionVault>>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+    
+    #[account(mut)]
+    pub joiner: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Ready<'info> {
+    #[account(
+        mut,
+        constraint = match_account.creator == player.key() ||
+            match_account.joiner == player.key() @ PV3Error::InvalidParticipant
+    )]
+    pub match_account: Account<'info, Match>,
+    
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitResult<'info> {
+    #[account(mut)]
+    pub match_account: Account<'info, Match>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", match_account.key().as_ref()],
+        bump = match_escrow.bump
+    )]
+    pub match_escrow: Account<'info, Escrow>,
+    
+    #[account(mut)]
+    /// CHECK: Winner account to receive funds
+    pub winner: AccountInfo<'info>,
+    
+    #[account(mut, address = config.treasury)]
+    /// CHECK: Treasury account for platform fees
+    pub treasury: AccountInfo<'info>,
+    
+    #[account(mut, address = config.referral_pool)]
+    /// CHECK: Referral pool account
+    pub referral_pool: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+    
+    /// CHECK: This is the instructions sysvar
+    #[account(address = instructions::ID)]
+    pub instruction_sysvar: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeMatch<'info> {
+    #[account(mut)]
+    pub match_account: Account<'info, Match>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", match_account.key().as_ref()],
+        bump = match_escrow.bump
+    )]
+    pub match_escrow: Account<'info, Escrow>,
+    
+    #[account(mut, address = match_account.winner @ PV3Error::InvalidWinner)]
+    /// CHECK: Recorded winner to receive funds
+    pub winner: AccountInfo<'info>,
+    
+    // Counted against the abandoner once an abandonment verdict pays out
+    #[account(
+        mut,
+        seeds = [b"profile", match_account.abandoned_by.as_ref()],
+        bump = abandoner_profile.bump
+    )]
+    pub abandoner_profile: Option<Account<'info, PlayerProfile>>,
+    
+    #[account(mut, address = config.treasury)]
+    /// CHECK: Treasury account for platform fees
+    pub treasury: AccountInfo<'info>,
     
-    pub fn emergency_pause(ctx: Context<EmergencyAction>) -> Result<()> {
-        admin::emergency_pause(ctx)
-    }
+    #[account(mut, address = config.referral_pool)]
+    /// CHECK: Referral pool account
+    pub referral_pool: AccountInfo<'info>,
     
-    pub fn emergency_unpause(ctx: Context<EmergencyAction>) -> Result<()> {
-        admin::emergency_unpause(ctx)
-    }
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeDraw<'info> {
+    #[account(mut)]
+    pub match_account: Account<'info, Match>,
     
-    pub fn update_fees(
-        ctx: Context<EmergencyAction>, 
-        platform_fee_bps: u16,
-        treasury_fee_bps: u16,
-        referral_fee_bps: u16,
-    ) -> Result<()> {
-        admin::update_fees(ctx, platform_fee_bps, treasury_fee_bps, referral_fee_bps)
-    }
+    #[account(
+        mut,
+        seeds = [b"escrow", match_account.key().as_ref()],
+        bump = match_escrow.bump
+    )]
+    pub match_escrow: Account<'info, Escrow>,
     
-    pub fn recover_inactive_vault(
-        ctx: Context<RecoverVault>,
-        inactivity_threshold_hours: u64,
-    ) -> Result<()> {
-        admin::recover_inactive_vault(ctx, inactivity_threshold_hours)
-    }
+    #[account(mut, address = match_account.creator @ PV3Error::InvalidParticipant)]
+    /// CHECK: Creator account to receive their share
+    pub creator: AccountInfo<'info>,
+    
+    #[account(mut, address = match_account.joiner @ PV3Error::InvalidParticipant)]
+    /// CHECK: Joiner account to receive their share
+    pub joiner: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"session", match_account.creator.as_ref()],
+        bump = creator_session_vault.bump
+    )]
+    pub creator_session_vault: Option<Account<'info, SessionVault>>,
+    
+    #[account(
+        mut,
+        seeds = [b"session", match_account.joiner.as_ref()],
+        bump = joiner_session_vault.bump
+    )]
+    pub joiner_session_vault: Option<Account<'info, SessionVault>>,
+    
+    #[account(mut, address = config.treasury)]
+    /// CHECK: Treasury account for platform fees
+    pub treasury: AccountInfo<'info>,
+    
+    #[account(mut, address = config.referral_pool)]
+    /// CHECK: Referral pool account
+    pub referral_pool: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
 }
 
-// Helper functions
-fn create_result_message(match_id: Pubkey, winner: Pubkey, result_hash: [u8; 32]) -> Vec<u8> {
-    let mut message = Vec::new();
-    message.extend_from_slice(&match_id.to_bytes());
-    message.extend_from_slice(&winner.to_bytes());
-    message.extend_from_slice(&result_hash);
-    message
+#[derive(Accounts)]
+pub struct FlagMatch<'info> {
+    #[account(mut)]
+    pub match_account: Account<'info, Match>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+    
+    pub flagger: Signer<'info>,
 }
 
-fn verify_ed25519_signature(
-    signature: &[u8; 64],
-    message: &[u8],
-    pubkey: &[u8; 32],
-    instruction: &Instruction,
-) -> Result<()> {
-    // Verify that the ed25519 instruction contains our signature verification
-    require!(
-        instruction.data.len() >= 112, // ed25519 instruction minimum size
-        PV3Error::InvalidSignatureData
-    );
+#[derive(Accounts)]
+pub struct ResolveFlaggedMatch<'info> {
+    #[account(mut)]
+    pub match_account: Account<'info, Match>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", match_account.key().as_ref()],
+        bump = match_escrow.bump
+    )]
+    pub match_escrow: Account<'info, Escrow>,
     
-    // Parse ed25519 instruction data to verify signature matches
-    let num_signatures = instruction.data[0];
-    require!(num_signatures == 1, PV3Error::InvalidSignatureCount);
+    #[account(
+        mut,
+        constraint = match_account.winner == Pubkey::default() ||
+            winner.key() == match_account.winner @ PV3Error::InvalidWinner
+    )]
+    /// CHECK: Recorded winner, paid if the flag is dismissed. Unused for a pending draw
+    pub winner: AccountInfo<'info>,
     
-    let signature_offset = 1;
-    let pubkey_offset = signature_offset + 64;
-    let message_data_offset = pubkey_offset + 32;
+    // Counted against the abandoner once an abandonment verdict pays out
+    #[account(
+        mut,
+        seeds = [b"profile", match_account.abandoned_by.as_ref()],
+        bump = abandoner_profile.bump
+    )]
+    pub abandoner_profile: Option<Account<'info, PlayerProfile>>,
     
-    let inst_signature = &instruction.data[signature_offset..signature_offset + 64];
-    let inst_pubkey = &instruction.data[pubkey_offset..pubkey_offset + 32];
+    #[account(mut, address = match_account.creator @ PV3Error::InvalidParticipant)]
+    /// CHECK: Creator account for refund
+    pub creator: AccountInfo<'info>,
     
-    require!(
-        inst_signature == signature,
-        PV3Error::SignatureMismatch
-    );
-    require!(
-        inst_pubkey == pubkey,
-        PV3Error::PublicKeyMismatch
-    );
+    #[account(mut, address = match_account.joiner @ PV3Error::InvalidParticipant)]
+    /// CHECK: Joiner account for refund
+    pub joiner: AccountInfo<'info>,
     
-    Ok(())
+    #[account(
+        mut,
+        seeds = [b"session", match_account.creator.as_ref()],
+        bump = creator_session_vault.bump
+    )]
+    pub creator_session_vault: Option<Account<'info, SessionVault>>,
+    
+    #[account(
+        mut,
+        seeds = [b"session", match_account.joiner.as_ref()],
+        bump = joiner_session_vault.bump
+    )]
+    pub joiner_session_vault: Option<Account<'info, SessionVault>>,
+    
+    #[account(mut, address = config.treasury)]
+    /// CHECK: Treasury account for platform fees
+    pub treasury: AccountInfo<'info>,
+    
+    #[account(mut, address = config.referral_pool)]
+    /// CHECK: Referral pool account
+    pub referral_pool: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin_signers.contains(&admin1.key()) @ PV3Error::UnauthorizedAdmin,
+        constraint = config.admin_signers.contains(&admin2.key()) @ PV3Error::UnauthorizedAdmin,
+        constraint = admin1.key() != admin2.key() @ PV3Error::InsufficientAdminSignatures
+    )]
+    pub config: Account<'info, PlatformConfig>,
+    
+    // 2-of-3 multisig requirement
+    pub admin1: Signer<'info>,
+    pub admin2: Signer<'info>,
 }
 
-// Account structures
 #[derive(Accounts)]
-pub struct Initialize<'info> {
+pub struct ForfeitMatch<'info> {
     #[account(
-        init,
+        mut,
+        constraint = match_account.creator == player.key() ||
+            match_account.joiner == player.key() @ PV3Error::InvalidParticipant
+    )]
+    pub match_account: Account<'info, Match>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", match_account.key().as_ref()],
+        bump = match_escrow.bump
+    )]
+    pub match_escrow: Account<'info, Escrow>,
+    
+    pub player: Signer<'info>,
+    
+    #[account(mut)]
+    /// CHECK: The other participant, verified against the match in the handler
+    pub opponent: AccountInfo<'info>,
+    
+    #[account(mut, address = config.treasury)]
+    /// CHECK: Treasury account for platform fees
+    pub treasury: AccountInfo<'info>,
+    
+    #[account(mut, address = config.referral_pool)]
+    /// CHECK: Referral pool account
+    pub referral_pool: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+}
+
+#[derive(Accounts)]
+pub struct AbortMatch<'info> {
+    #[account(
+        mut,
+        has_one = creator @ PV3Error::InvalidParticipant,
+        has_one = joiner @ PV3Error::InvalidParticipant
+    )]
+    pub match_account: Account<'info, Match>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", match_account.key().as_ref()],
+        bump = match_escrow.bump
+    )]
+    pub match_escrow: Account<'info, Escrow>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(mut)]
+    pub joiner: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"session", match_account.creator.as_ref()],
+        bump = creator_session_vault.bump
+    )]
+    pub creator_session_vault: Option<Account<'info, SessionVault>>,
+    
+    #[account(
+        mut,
+        seeds = [b"session", match_account.joiner.as_ref()],
+        bump = joiner_session_vault.bump
+    )]
+    pub joiner_session_vault: Option<Account<'info, SessionVault>>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitDraw<'info> {
+    #[account(mut)]
+    pub match_account: Account<'info, Match>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", match_account.key().as_ref()],
+        bump = match_escrow.bump
+    )]
+    pub match_escrow: Account<'info, Escrow>,
+    
+    #[account(mut, address = match_account.creator @ PV3Error::InvalidParticipant)]
+    /// CHECK: Creator account to receive their share
+    pub creator: AccountInfo<'info>,
+    
+    #[account(mut, address = match_account.joiner @ PV3Error::InvalidParticipant)]
+    /// CHECK: Joiner account to receive their share
+    pub joiner: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"session", match_account.creator.as_ref()],
+        bump = creator_session_vault.bump
+    )]
+    pub creator_session_vault: Option<Account<'info, SessionVault>>,
+    
+    #[account(
+        mut,
+        seeds = [b"session", match_account.joiner.as_ref()],
+        bump = joiner_session_vault.bump
+    )]
+    pub joiner_session_vault: Option<Account<'info, SessionVault>>,
+    
+    #[account(mut, address = config.treasury)]
+    /// CHECK: Treasury account for platform fees
+    pub treasury: AccountInfo<'info>,
+    
+    #[account(mut, address = config.referral_pool)]
+    /// CHECK: Referral pool account
+    pub referral_pool: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+    
+    /// CHECK: This is the instructions sysvar
+    #[account(address = instructions::ID)]
+    pub instruction_sysvar: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(abandoner: Pubkey)]
+pub struct SubmitAbandonment<'info> {
+    #[account(mut)]
+    pub match_account: Account<'info, Match>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", match_account.key().as_ref()],
+        bump = match_escrow.bump
+    )]
+    pub match_escrow: Account<'info, Escrow>,
+    
+    #[account(mut)]
+    /// CHECK: The player who stayed, verified against the match in the handler
+    pub winner: AccountInfo<'info>,
+    
+    // Opened on the spot if the abandoner never created one, so the count can't be skipped
+    #[account(
+        init_if_needed,
         payer = payer,
-        space = 8 + PlatformConfig::INIT_SPACE,
+        space = 8 + PlayerProfile::INIT_SPACE,
+        seeds = [b"profile", abandoner.as_ref()],
+        bump
+    )]
+    pub abandoner_profile: Account<'info, PlayerProfile>,
+    
+    #[account(mut, address = config.treasury)]
+    /// CHECK: Treasury account for platform fees
+    pub treasury: AccountInfo<'info>,
+    
+    #[account(mut, address = config.referral_pool)]
+    /// CHECK: Referral pool account
+    pub referral_pool: AccountInfo<'info>,
+    
+    #[account(
+        mut,
         seeds = [b"config"],
-        bump
+        bump = config.bump
     )]
     pub config: Account<'info, PlatformConfig>,
     
+    /// CHECK: This is the instructions sysvar
+    #[account(address = instructions::ID)]
+    pub instruction_sysvar: AccountInfo<'info>,
+    
+    // Whoever relays the verdict, the ed25519 instruction carries the verifier's authority
     #[account(mut)]
     pub payer: Signer<'info>,
     
@@ -419,137 +2842,248 @@ pub struct Initialize<'info> {
 }
 
 #[derive(Accounts)]
-#[instruction(game_id: String)]
-pub struct CreateMatch<'info> {
+pub struct SubmitCreatorScore<'info> {
+    #[account(mut)]
+    pub match_account: Account<'info, Match>,
+    
     #[account(
-        init,
-        payer = creator,
-        space = 8 + Match::INIT_SPACE,
-        seeds = [b"match", creator.key().as_ref(), game_id.as_bytes(), &Clock::get().unwrap().unix_timestamp.to_le_bytes()],
-        bump
+        seeds = [b"config"],
+        bump = config.bump
     )]
+    pub config: Account<'info, PlatformConfig>,
+    
+    /// CHECK: This is the instructions sysvar
+    #[account(address = instructions::ID)]
+    pub instruction_sysvar: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitJoinerScore<'info> {
+    #[account(mut)]
     pub match_account: Account<'info, Match>,
     
     #[account(
+        mut,
         seeds = [b"escrow", match_account.key().as_ref()],
-        bump
+        bump = match_escrow.bump
     )]
-    /// CHECK: This is the PDA that holds the match funds
-    pub match_escrow: AccountInfo<'info>,
+    pub match_escrow: Account<'info, Escrow>,
+    
+    #[account(mut, address = match_account.creator @ PV3Error::InvalidParticipant)]
+    /// CHECK: Creator account, paid if they win or draw
+    pub creator: AccountInfo<'info>,
+    
+    #[account(mut, address = match_account.joiner @ PV3Error::InvalidParticipant)]
+    /// CHECK: Joiner account, paid if they win or draw
+    pub joiner: AccountInfo<'info>,
     
     #[account(
         mut,
-        seeds = [b"session", creator.key().as_ref()],
-        bump
+        seeds = [b"session", match_account.creator.as_ref()],
+        bump = creator_session_vault.bump
     )]
-    pub session_vault: Option<Account<'info, SessionVault>>,
+    pub creator_session_vault: Option<Account<'info, SessionVault>>,
+    
+    #[account(
+        mut,
+        seeds = [b"session", match_account.joiner.as_ref()],
+        bump = joiner_session_vault.bump
+    )]
+    pub joiner_session_vault: Option<Account<'info, SessionVault>>,
+    
+    #[account(mut, address = config.treasury)]
+    /// CHECK: Treasury account for platform fees
+    pub treasury: AccountInfo<'info>,
+    
+    #[account(mut, address = config.referral_pool)]
+    /// CHECK: Referral pool account
+    pub referral_pool: AccountInfo<'info>,
     
     #[account(
+        mut,
         seeds = [b"config"],
         bump = config.bump
     )]
     pub config: Account<'info, PlatformConfig>,
     
+    /// CHECK: This is the instructions sysvar
+    #[account(address = instructions::ID)]
+    pub instruction_sysvar: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimScoreTimeout<'info> {
+    #[account(
+        mut,
+        has_one = creator @ PV3Error::NotMatchCreator
+    )]
+    pub match_account: Account<'info, Match>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", match_account.key().as_ref()],
+        bump = match_escrow.bump
+    )]
+    pub match_escrow: Account<'info, Escrow>,
+    
     #[account(mut)]
     pub creator: Signer<'info>,
     
-    pub system_program: Program<'info, System>,
+    #[account(mut, address = config.treasury)]
+    /// CHECK: Treasury account for platform fees
+    pub treasury: AccountInfo<'info>,
+    
+    #[account(mut, address = config.referral_pool)]
+    /// CHECK: Referral pool account
+    pub referral_pool: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
 }
 
 #[derive(Accounts)]
-pub struct JoinMatch<'info> {
+pub struct RefundMatch<'info> {
     #[account(mut)]
     pub match_account: Account<'info, Match>,
     
     #[account(
         mut,
         seeds = [b"escrow", match_account.key().as_ref()],
-        bump
+        bump = match_escrow.bump
+    )]
+    pub match_escrow: Account<'info, Escrow>,
+    
+    #[account(mut, address = match_account.creator @ PV3Error::InvalidParticipant)]
+    /// CHECK: Creator account for refund
+    pub creator: AccountInfo<'info>,
+    
+    #[account(mut)]
+    /// CHECK: Joiner account for refund
+    pub joiner: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"session", match_account.creator.as_ref()],
+        bump = creator_session_vault.bump
     )]
-    /// CHECK: This is the PDA that holds the match funds
-    pub match_escrow: AccountInfo<'info>,
+    pub creator_session_vault: Option<Account<'info, SessionVault>>,
     
     #[account(
         mut,
-        seeds = [b"session", joiner.key().as_ref()],
-        bump
+        seeds = [b"session", match_account.joiner.as_ref()],
+        bump = joiner_session_vault.bump
     )]
-    pub session_vault: Option<Account<'info, SessionVault>>,
+    pub joiner_session_vault: Option<Account<'info, SessionVault>>,
     
     #[account(
+        mut,
         seeds = [b"config"],
         bump = config.bump
     )]
     pub config: Account<'info, PlatformConfig>,
     
+    // Any caller; receives the crank bounty
     #[account(mut)]
-    pub joiner: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
+    pub keeper: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct SubmitResult<'info> {
-    #[account(mut)]
+pub struct CancelMatch<'info> {
+    #[account(
+        mut,
+        has_one = creator @ PV3Error::NotMatchCreator
+    )]
     pub match_account: Account<'info, Match>,
     
     #[account(
         mut,
         seeds = [b"escrow", match_account.key().as_ref()],
-        bump
+        bump = match_escrow.bump
     )]
-    /// CHECK: This is the PDA that holds the match funds
-    pub match_escrow: AccountInfo<'info>,
-    
-    #[account(mut)]
-    /// CHECK: Winner account to receive funds
-    pub winner: AccountInfo<'info>,
-    
-    #[account(mut)]
-    /// CHECK: Treasury account for platform fees
-    pub treasury: AccountInfo<'info>,
+    pub match_escrow: Account<'info, Escrow>,
     
     #[account(mut)]
-    /// CHECK: Referral pool account
-    pub referral_pool: AccountInfo<'info>,
+    pub creator: Signer<'info>,
     
     #[account(
         mut,
-        seeds = [b"config"],
-        bump = config.bump
+        seeds = [b"session", match_account.creator.as_ref()],
+        bump = creator_session_vault.bump
     )]
-    pub config: Account<'info, PlatformConfig>,
+    pub creator_session_vault: Option<Account<'info, SessionVault>>,
+}
+
+// Indexing is a separate step so creates never race for the next number's address.
+// The creator pays, and gets the rent back when the match is closed
+#[derive(Accounts)]
+pub struct IndexMatch<'info> {
+    #[account(has_one = creator @ PV3Error::NotMatchCreator)]
+    pub match_account: Account<'info, Match>,
     
-    /// CHECK: This is the instructions sysvar
-    #[account(address = instructions::ID)]
-    pub instruction_sysvar: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + MatchIndex::INIT_SPACE,
+        seeds = [b"match_index", match_account.match_number.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub match_index: Account<'info, MatchIndex>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
     
-    pub verifier: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct RefundMatch<'info> {
-    #[account(mut)]
+pub struct CloseMatch<'info> {
+    #[account(
+        mut,
+        has_one = creator @ PV3Error::NotMatchCreator,
+        close = creator
+    )]
     pub match_account: Account<'info, Match>,
     
     #[account(
         mut,
         seeds = [b"escrow", match_account.key().as_ref()],
+        bump = match_escrow.bump,
+        close = creator
+    )]
+    pub match_escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        seeds = [b"match_index", match_account.match_number.to_le_bytes().as_ref()],
+        bump
+    )]
+    /// CHECK: Index entry for the match, closed only if it was created
+    pub match_index: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"dispute", match_account.key().as_ref()],
         bump
     )]
-    /// CHECK: This is the PDA that holds the match funds
-    pub match_escrow: AccountInfo<'info>,
+    /// CHECK: Dispute on the match, closed only if one was opened
+    pub dispute: UncheckedAccount<'info>,
     
     #[account(mut)]
-    /// CHECK: Creator account for refund
-    pub creator: AccountInfo<'info>,
+    /// CHECK: Required when a dispute exists, checked against its opener in the handler
+    pub dispute_opener: Option<UncheckedAccount<'info>>,
     
     #[account(mut)]
-    /// CHECK: Joiner account for refund
-    pub joiner: AccountInfo<'info>,
+    pub creator: Signer<'info>,
 }
 
 // Account data structures
+
+// Fields were added mid-struct since the first release, so a config written by an
+// earlier build no longer deserializes. Requires a fresh deploy, see the README
 #[account]
 #[derive(InitSpace)]
 pub struct PlatformConfig {
@@ -559,14 +3093,26 @@ pub struct PlatformConfig {
     pub platform_fee_bps: u16,    // 650 = 6.5%
     pub treasury_fee_bps: u16,    // 550 = 5.5%
     pub referral_fee_bps: u16,    // 100 = 1%
+    pub min_wager: u64,           // Lamports, overridable per game
+    pub max_wager: u64,
+    #[max_len(16)]
+    pub wager_tiers: Vec<u64>,    // Allowed wagers in lamports, empty = any amount in bounds
+    pub draw_fee_policy: FeePolicy,
+    pub abandonment_fee_policy: FeePolicy, // NoFee = the remaining player takes the whole pot
+    pub challenge_period: i64,    // Seconds a verdict can be flagged, 0 = pay immediately
+    pub crank_bounty: u64,        // Lamports paid to whoever refunds an expired match
+    pub crank_budget: u64,        // Bounty lamports held by the config account
     pub is_paused: bool,
-    pub total_matches: u64,
+    pub total_matches: u64,       // Settled matches
     pub total_volume: u64,
+    pub next_match_number: u64,   // Sequential ID for the next created match
     #[max_len(3)]
     pub admin_signers: [Pubkey; 3], // 2-of-3 multisig
     pub bump: u8,
 }
 
+// Same as the config: the layout changed mid-struct and match PDAs are now seeded by
+// creator nonce, so matches from an earlier build can't be read or settled
 #[account]
 #[derive(InitSpace)]
 pub struct Match {
@@ -575,12 +3121,54 @@ pub struct Match {
     #[max_len(50)]
     pub game_id: String,
     pub wager_amount: u64,
+    pub wager_tier: Option<u8>, // Index into the config tiers at creation time
     pub total_pot: u64,
-    pub expiry_time: i64,
+    pub join_deadline: i64,     // Refundable if nobody joins by then
+    pub result_window: i64,     // Seconds allowed for play once joined
+    pub result_deadline: i64,   // Refundable if no result by then
     pub status: MatchStatus,
     pub winner: Pubkey,
     pub created_at: i64,
     pub result_hash: [u8; 32],
+    pub settle_after: i64,      // End of the challenge window for a pending verdict
+    pub flagged_by: Pubkey,
+    pub invited_opponent: Pubkey,     // Pubkey::default() = open to anyone
+    pub join_secret_hash: [u8; 32],   // sha256 of the join code, zeroed = none
+    pub rematch_of: Pubkey,           // Previous match for rematches, default otherwise
+    pub creator_nonce: u64,           // Seed nonce from the creator's profile
+    pub match_number: u64,            // Global sequential ID, e.g. "match #123456"
+    pub series_length: u8,            // Best-of-N, 1 for a single game
+    pub creator_wins: u8,
+    pub joiner_wins: u8,
+    pub is_async: bool,               // Score challenge: creator plays first, joiner later
+    pub creator_score: Option<u64>,
+    pub joiner_score: Option<u64>,
+    pub ready_window: i64,            // Seconds to confirm after join, 0 = no ready check
+    pub ready_deadline: i64,
+    pub creator_ready: bool,
+    pub joiner_ready: bool,
+    pub creator_no_show: bool,        // Set when a missed ready check refunds the match
+    pub joiner_no_show: bool,
+    pub creator_funding: FundingSource, // Refunds and draw shares go back to the same source
+    pub joiner_funding: FundingSource,
+    pub abandoned_by: Pubkey,         // Set by an abandonment verdict, default otherwise
+    pub bump: u8,
+}
+
+impl Match {
+    pub fn hold_for_challenge(&mut self, config: &PlatformConfig) -> Result<bool> {
+        hold_for_challenge(config, &mut self.status, &mut self.settle_after)
+    }
+}
+
+// Lookup entry seeded by match number, so tools can resolve "match #N" without
+// scanning program accounts. Points at a match, lobby or team match, and is closed
+// together with it
+#[account]
+#[derive(InitSpace)]
+pub struct MatchIndex {
+    pub match_number: u64,
+    pub match_account: Pubkey,        // Match, Lobby or TeamMatch account
     pub bump: u8,
 }
 
@@ -591,8 +3179,51 @@ pub enum MatchStatus {
     Completed,
     Cancelled,
     Refunded,
+    Drawn,
+    Aborted,
+    PendingSettlement,
+    Flagged,
+    Disputed,
+    AwaitingReady,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum FeePolicy {
+    NoFee,
+    NormalFee,
+}
+
+// Where a player's wager was paid from
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum FundingSource {
+    Wallet,
+    SessionVault,
 }
 
+pub const DRAW_VERDICT_TAG: &[u8] = b"pv3:draw";
+pub const GAME_VERDICT_TAG: &[u8] = b"pv3:game";
+pub const SCORE_VERDICT_TAG: &[u8] = b"pv3:score";
+pub const ABANDON_VERDICT_TAG: &[u8] = b"pv3:abandon";
+pub const MAX_SERIES_LENGTH: u8 = 7;
+pub const MAX_READY_WINDOW: i64 = 5 * 60;
+pub const MAX_RESULT_WINDOW: i64 = 7 * 24 * 3600; // Same cap as the challenge period
+pub const CRANK_BOUNTY_DELAY: i64 = 60 * 60; // Players get this long to reclaim wagers before keepers earn a bounty
+
+// Whitepaper wager tiers: 0.1, 0.2, 0.3, 0.5, 1, 2, 3, 4, 5 and 10 SOL
+pub const DEFAULT_WAGER_TIERS: [u64; 10] = [
+    100_000_000,
+    200_000_000,
+    300_000_000,
+    500_000_000,
+    1_000_000_000,
+    2_000_000_000,
+    3_000_000_000,
+    4_000_000_000,
+    5_000_000_000,
+    10_000_000_000,
+];
+pub const MAX_WAGER_TIERS: usize = 16;
+
 // Events
 #[event]
 pub struct PlatformInitialized {
@@ -604,10 +3235,34 @@ pub struct PlatformInitialized {
 #[event]
 pub struct MatchCreated {
     pub match_id: Pubkey,
+    pub match_number: u64,
+    pub creator: Pubkey,
+    pub nonce: u64,
+    pub game_id: String,
+    pub wager_amount: u64,
+    pub wager_tier: Option<u8>,
+    pub join_deadline: i64,
+    pub result_window: i64,
+    pub invited_opponent: Pubkey,
+    pub requires_join_secret: bool,
+    pub series_length: u8,
+    pub is_async: bool,
+    pub ready_window: i64,
+    pub funding_source: FundingSource,
+}
+
+#[event]
+pub struct RematchCreated {
+    pub match_id: Pubkey,
+    pub match_number: u64,
+    pub previous_match: Pubkey,
     pub creator: Pubkey,
+    pub opponent: Pubkey,
     pub game_id: String,
     pub wager_amount: u64,
-    pub expiry_time: i64,
+    pub wager_tier: Option<u8>,
+    pub join_deadline: i64,
+    pub funding_source: FundingSource,
 }
 
 #[event]
@@ -615,16 +3270,112 @@ pub struct MatchJoined {
     pub match_id: Pubkey,
     pub joiner: Pubkey,
     pub total_pot: u64,
+    pub ready_deadline: i64,    // Zero when the match skips the ready check
+    pub result_deadline: i64,
+    pub funding_source: FundingSource,
+}
+
+#[event]
+pub struct PlayerReady {
+    pub match_id: Pubkey,
+    pub player: Pubkey,
+    pub started: bool,
+    pub result_deadline: i64,
+}
+
+#[event]
+pub struct ReadyCheckFailed {
+    pub match_id: Pubkey,
+    pub creator_no_show: bool,
+    pub joiner_no_show: bool,
 }
 
 #[event]
 pub struct MatchCompleted {
     pub match_id: Pubkey,
+    pub wager_tier: Option<u8>,
+    pub winner: Pubkey,
+    pub winner_amount: u64,
+    pub platform_fee: u64,
+    pub treasury_fee: u64,
+    pub referral_fee: u64,
+}
+
+#[event]
+pub struct SeriesGameRecorded {
+    pub match_id: Pubkey,
+    pub game_number: u8,
+    pub winner: Pubkey,
+    pub result_hash: [u8; 32],
+    pub creator_wins: u8,
+    pub joiner_wins: u8,
+}
+
+#[event]
+pub struct ScoreSubmitted {
+    pub match_id: Pubkey,
+    pub player: Pubkey,
+    pub score: u64,
+    pub result_hash: [u8; 32],
+}
+
+#[event]
+pub struct MatchResultPending {
+    pub match_id: Pubkey,
+    pub winner: Pubkey, // Pubkey::default() for a draw
+    pub settle_after: i64,
+}
+
+#[event]
+pub struct MatchFlagged {
+    pub match_id: Pubkey,
+    pub flagged_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FlaggedMatchResolved {
+    pub match_id: Pubkey,
+    pub payout_released: bool,
+    pub admin1: Pubkey,
+    pub admin2: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MatchForfeited {
+    pub match_id: Pubkey,
+    pub wager_tier: Option<u8>,
+    pub forfeited_by: Pubkey,
+    pub winner: Pubkey,
+    pub winner_amount: u64,
+    pub platform_fee: u64,
+    pub treasury_fee: u64,
+    pub referral_fee: u64,
+}
+
+#[event]
+pub struct MatchAbandoned {
+    pub match_id: Pubkey,
+    pub wager_tier: Option<u8>,
+    pub abandoned_by: Pubkey,
     pub winner: Pubkey,
     pub winner_amount: u64,
     pub platform_fee: u64,
     pub treasury_fee: u64,
     pub referral_fee: u64,
+    pub abandonments: u32, // Abandoner's running count
+}
+
+#[event]
+pub struct MatchDrawn {
+    pub match_id: Pubkey,
+    pub wager_tier: Option<u8>,
+    pub creator_amount: u64,
+    pub joiner_amount: u64,
+    pub platform_fee: u64,
+    pub treasury_fee: u64,
+    pub referral_fee: u64,
 }
 
 #[event]
@@ -633,16 +3384,62 @@ pub struct MatchRefunded {
     pub refund_amount: u64,
 }
 
+#[event]
+pub struct ExpiredMatchCranked {
+    pub match_id: Pubkey,
+    pub keeper: Pubkey,
+    pub keeper_bounty: u64,
+    pub remaining_budget: u64,
+}
+
+#[event]
+pub struct MatchClosed {
+    pub match_id: Pubkey,
+    pub creator: Pubkey,
+    pub rent_reclaimed: u64,
+    pub escrow_swept: u64,
+}
+
+#[event]
+pub struct MatchIndexed {
+    pub match_number: u64,
+    pub match_account: Pubkey,
+}
+
+#[event]
+pub struct MatchAborted {
+    pub match_id: Pubkey,
+    pub creator: Pubkey,
+    pub joiner: Pubkey,
+    pub refund_amount: u64, // per player
+}
+
+#[event]
+pub struct MatchCancelled {
+    pub match_id: Pubkey,
+    pub creator: Pubkey,
+    pub refund_amount: u64,
+}
+
+// Emitted right before WagerTooLow/WagerTooHigh so the failed transaction's logs
+// carry the limits that applied
+#[event]
+pub struct WagerOutOfBounds {
+    pub wager_amount: u64,
+    pub min_wager: u64,
+    pub max_wager: u64,
+}
+
 // Error definitions
 #[error_code]
 pub enum PV3Error {
     #[msg("Platform is currently paused")]
     PlatformPaused,
-    #[msg("Wager amount is too low (minimum 0.1 SOL)")]
+    #[msg("Wager amount is below the active minimum")]
     WagerTooLow,
-    #[msg("Wager amount is too high (maximum 10 SOL)")]
+    #[msg("Wager amount is above the active maximum")]
     WagerTooHigh,
-    #[msg("Invalid expiry time")]
+    #[msg("Invalid join deadline")]
     InvalidExpiryTime,
     #[msg("Match is not available for joining")]
     MatchNotAvailable,
@@ -676,4 +3473,126 @@ pub enum PV3Error {
     UnauthorizedAdmin,
     #[msg("Insufficient admin signatures")]
     InsufficientAdminSignatures,
+    #[msg("Only the match creator can perform this action")]
+    NotMatchCreator,
+    #[msg("Match can only be cancelled while waiting for a player")]
+    CancelNotAllowed,
+    #[msg("Only the invited opponent can join this match")]
+    NotInvitedOpponent,
+    #[msg("Missing or invalid join secret")]
+    InvalidJoinSecret,
+    #[msg("Account does not match the match participant")]
+    InvalidParticipant,
+    #[msg("Result window must be between 1 second and 7 days")]
+    InvalidResultWindow,
+    #[msg("Join deadline has not passed yet")]
+    JoinDeadlineNotReached,
+    #[msg("Result deadline has not passed yet")]
+    ResultDeadlineNotReached,
+    #[msg("Match has already been settled")]
+    MatchAlreadySettled,
+    #[msg("Match has already been refunded")]
+    MatchAlreadyRefunded,
+    #[msg("Match has already been cancelled")]
+    MatchAlreadyCancelled,
+    #[msg("Match has already been aborted")]
+    MatchAlreadyAborted,
+    #[msg("Match is not pending settlement")]
+    MatchNotPendingSettlement,
+    #[msg("Challenge period is still active")]
+    ChallengePeriodActive,
+    #[msg("Challenge period has ended")]
+    ChallengePeriodEnded,
+    #[msg("Only the losing player or an admin can flag a match")]
+    UnauthorizedFlag,
+    #[msg("Match has been flagged for review")]
+    MatchFlagged,
+    #[msg("Match is not flagged")]
+    MatchNotFlagged,
+    #[msg("Match cannot be disputed in its current state")]
+    DisputeNotAllowed,
+    #[msg("Match is not under dispute")]
+    MatchNotDisputed,
+    #[msg("Match has no recorded verdict to arbitrate")]
+    NoVerdictToArbitrate,
+    #[msg("Rematch requires a finished match")]
+    RematchNotAllowed,
+    #[msg("Match has not reached a terminal state")]
+    MatchNotSettled,
+    #[msg("Match nonce does not match the creator's next nonce")]
+    InvalidMatchNonce,
+    #[msg("Invalid wager bounds")]
+    InvalidWagerBounds,
+    #[msg("Wager amount is not one of the allowed tiers")]
+    WagerNotATier,
+    #[msg("Wager tiers must be ascending, within the wager bounds and at most 16 entries")]
+    InvalidWagerTiers,
+    #[msg("Series length must be odd and at most 7")]
+    InvalidSeriesLength,
+    #[msg("Match is not a series")]
+    NotASeriesMatch,
+    #[msg("Series matches must report results game by game")]
+    SeriesResultRequired,
+    #[msg("Game number is out of order")]
+    InvalidGameNumber,
+    #[msg("Lobby size must be between 2 and 8 players")]
+    InvalidLobbySize,
+    #[msg("Payout table must have one entry per paid rank and sum to 10000 bps")]
+    InvalidPayoutTable,
+    #[msg("Lobby is full")]
+    LobbyFull,
+    #[msg("Player already joined this lobby")]
+    AlreadyInLobby,
+    #[msg("Not enough players to fill the payout table")]
+    NotEnoughPlayers,
+    #[msg("Ranking does not match the lobby entrants")]
+    InvalidRanking,
+    #[msg("That team is already full")]
+    TeamFull,
+    #[msg("Player is already on a team in this match")]
+    AlreadyOnTeam,
+    #[msg("Async score matches cannot be a series")]
+    AsyncSeriesNotSupported,
+    #[msg("Match is not an async score match")]
+    NotAnAsyncMatch,
+    #[msg("Async matches must be settled with signed scores")]
+    ScoreResultRequired,
+    #[msg("Creator has not posted a score yet")]
+    AwaitingCreatorScore,
+    #[msg("Score already submitted")]
+    ScoreAlreadySubmitted,
+    #[msg("Score deadline has passed")]
+    ScoreDeadlinePassed,
+    #[msg("Ready window must be between 0 and 5 minutes")]
+    InvalidReadyWindow,
+    #[msg("Match is not awaiting a ready check")]
+    NotAwaitingReady,
+    #[msg("Ready window has closed")]
+    ReadyWindowClosed,
+    #[msg("Player is already ready")]
+    AlreadyReady,
+    #[msg("Ready deadline has not been reached")]
+    ReadyDeadlineNotReached,
+    #[msg("Session vault account is required to fund from the session")]
+    SessionVaultRequired,
+    #[msg("Batch accounts must come in complete per-match groups")]
+    InvalidBatchAccounts,
+    #[msg("Escrow account does not belong to this match")]
+    EscrowMismatch,
+    #[msg("Signed message does not match the expected verdict")]
+    MessageMismatch,
+    #[msg("Signer is not the configured verifier")]
+    UnauthorizedVerifier,
+    #[msg("Pending draws are settled with finalize_draw")]
+    PendingDraw,
+    #[msg("Pending verdict is not a draw")]
+    NotAPendingDraw,
+    #[msg("Every account in a batch entry must be writable")]
+    BatchEntryNotWritable,
+    #[msg("Dispute rent must go back to the player who opened it")]
+    DisputeOpenerMismatch,
+    #[msg("Challenge period must be between 0 and 7 days")]
+    InvalidChallengePeriod,
+    #[msg("Abandonment payouts need the abandoner's profile")]
+    AbandonerProfileRequired,
 } 
\ No newline at end of file
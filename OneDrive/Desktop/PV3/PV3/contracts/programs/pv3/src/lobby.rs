@@ -0,0 +1,868 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{hash::hash, sysvar::instructions};
+use crate::{
+    calculate_fees, close_if_initialized, fund_wager, hold_for_challenge, init_escrow,
+    init_match_index, next_match_number, pay_shares, record_settlement, refund_entries,
+    release_platform_fee, require_closable, require_finalizable, require_flaggable,
+    require_refund_expired, require_unsettled, require_wager_in_bounds,
+    verify_verifier_signature, wager_tier_index, Escrow, FundingSource, MatchClosed, MatchIndex,
+    MatchIndexed, MatchStatus, PlatformConfig, PlayerProfile, PV3Error, SessionVault,
+    MAX_RESULT_WINDOW,
+};
+
+/// Lobby Manager - Multi-player matches where up to 8 players stake the same wager
+/// and the pot is split over a verifier-signed final ranking using a payout table
+#[derive(Accounts)]
+#[instruction(nonce: u64, game_id: String)]
+pub struct CreateLobby<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Lobby::INIT_SPACE,
+        seeds = [b"lobby", creator.key().as_ref(), &nonce.to_le_bytes()],
+        bump
+    )]
+    pub lobby: Account<'info, Lobby>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", creator.key().as_ref()],
+        bump = creator_profile.bump
+    )]
+    pub creator_profile: Account<'info, PlayerProfile>,
+
+    #[account(
+        seeds = [b"game_config", hash(game_id.as_bytes()).as_ref()],
+        bump
+    )]
+    /// CHECK: Optional per-game wager bounds, may be uninitialized
+    pub game_config: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Escrow::INIT_SPACE,
+        seeds = [b"escrow", lobby.key().as_ref()],
+        bump
+    )]
+    pub lobby_escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"session", creator.key().as_ref()],
+        bump
+    )]
+    pub session_vault: Option<Account<'info, SessionVault>>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Indexing is a separate step so creates never race for the next number's address
+#[derive(Accounts)]
+pub struct IndexLobby<'info> {
+    #[account(has_one = creator @ PV3Error::NotMatchCreator)]
+    pub lobby: Account<'info, Lobby>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + MatchIndex::INIT_SPACE,
+        seeds = [b"match_index", lobby.match_number.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub match_index: Account<'info, MatchIndex>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinLobby<'info> {
+    #[account(mut)]
+    pub lobby: Account<'info, Lobby>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", lobby.key().as_ref()],
+        bump = lobby_escrow.bump
+    )]
+    pub lobby_escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"session", player.key().as_ref()],
+        bump
+    )]
+    pub session_vault: Option<Account<'info, SessionVault>>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct StartLobby<'info> {
+    #[account(
+        mut,
+        has_one = creator @ PV3Error::NotMatchCreator
+    )]
+    pub lobby: Account<'info, Lobby>,
+
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitLobbyResult<'info> {
+    #[account(mut)]
+    pub lobby: Account<'info, Lobby>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", lobby.key().as_ref()],
+        bump = lobby_escrow.bump
+    )]
+    pub lobby_escrow: Account<'info, Escrow>,
+
+    #[account(mut, address = config.treasury)]
+    /// CHECK: Treasury account for platform fees
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, address = config.referral_pool)]
+    /// CHECK: Referral pool account
+    pub referral_pool: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+
+    /// CHECK: This is the instructions sysvar
+    #[account(address = instructions::ID)]
+    pub instruction_sysvar: AccountInfo<'info>,
+    // remaining_accounts: paid recipients in rank order, one per payout table entry.
+    // Each is the player's wallet, or their session vault if that funded the entry
+}
+
+#[derive(Accounts)]
+pub struct FinalizeLobby<'info> {
+    #[account(mut)]
+    pub lobby: Account<'info, Lobby>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", lobby.key().as_ref()],
+        bump = lobby_escrow.bump
+    )]
+    pub lobby_escrow: Account<'info, Escrow>,
+
+    #[account(mut, address = config.treasury)]
+    /// CHECK: Treasury account for platform fees
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, address = config.referral_pool)]
+    /// CHECK: Referral pool account
+    pub referral_pool: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+    // remaining_accounts: paid recipients in rank order, as for submit_lobby_result
+}
+
+#[derive(Accounts)]
+pub struct FlagLobby<'info> {
+    #[account(mut)]
+    pub lobby: Account<'info, Lobby>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+
+    pub flagger: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveFlaggedLobby<'info> {
+    #[account(mut)]
+    pub lobby: Account<'info, Lobby>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", lobby.key().as_ref()],
+        bump = lobby_escrow.bump
+    )]
+    pub lobby_escrow: Account<'info, Escrow>,
+
+    #[account(mut, address = config.treasury)]
+    /// CHECK: Treasury account for platform fees
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, address = config.referral_pool)]
+    /// CHECK: Referral pool account
+    pub referral_pool: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin_signers.contains(&admin1.key()) @ PV3Error::UnauthorizedAdmin,
+        constraint = config.admin_signers.contains(&admin2.key()) @ PV3Error::UnauthorizedAdmin,
+        constraint = admin1.key() != admin2.key() @ PV3Error::InsufficientAdminSignatures
+    )]
+    pub config: Account<'info, PlatformConfig>,
+
+    // 2-of-3 multisig requirement
+    pub admin1: Signer<'info>,
+    pub admin2: Signer<'info>,
+    // remaining_accounts: paid recipients in rank order when releasing the payout,
+    // otherwise every entrant's refund target in join order
+}
+
+#[derive(Accounts)]
+pub struct RefundLobby<'info> {
+    #[account(mut)]
+    pub lobby: Account<'info, Lobby>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", lobby.key().as_ref()],
+        bump = lobby_escrow.bump
+    )]
+    pub lobby_escrow: Account<'info, Escrow>,
+    // remaining_accounts: every entrant's wallet or funding session vault, in join order
+}
+
+#[derive(Accounts)]
+pub struct CloseLobby<'info> {
+    #[account(
+        mut,
+        has_one = creator @ PV3Error::NotMatchCreator,
+        close = creator
+    )]
+    pub lobby: Account<'info, Lobby>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", lobby.key().as_ref()],
+        bump = lobby_escrow.bump,
+        close = creator
+    )]
+    pub lobby_escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"match_index", lobby.match_number.to_le_bytes().as_ref()],
+        bump
+    )]
+    /// CHECK: Index entry for the lobby, closed only if it was created
+    pub match_index: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Lobby {
+    pub creator: Pubkey,
+    #[max_len(50)]
+    pub game_id: String,
+    pub wager_amount: u64,
+    pub wager_tier: Option<u8>,
+    pub max_players: u8,
+    #[max_len(8)]
+    pub players: Vec<Pubkey>,       // Join order, creator first
+    #[max_len(8)]
+    pub player_funding: Vec<FundingSource>, // Where each player's wager came from, same order
+    #[max_len(8)]
+    pub payout_bps: Vec<u16>,       // Share of the net pot per rank, sums to 10000
+    pub total_pot: u64,
+    pub join_deadline: i64,
+    pub result_window: i64,
+    pub result_deadline: i64,
+    pub status: MatchStatus,
+    #[max_len(8)]
+    pub ranking: Vec<Pubkey>,       // Verified final ranking, first place first
+    pub result_hash: [u8; 32],
+    pub settle_after: i64,          // End of the challenge window for a pending ranking
+    pub created_at: i64,
+    pub creator_nonce: u64,
+    pub match_number: u64,          // Shares the global sequence with 1v1 matches
+    pub bump: u8,
+}
+
+impl Lobby {
+    pub fn hold_for_challenge(&mut self, config: &PlatformConfig) -> Result<bool> {
+        hold_for_challenge(config, &mut self.status, &mut self.settle_after)
+    }
+
+    pub fn funding_of(&self, player: &Pubkey) -> Result<FundingSource> {
+        let index = self.players
+            .iter()
+            .position(|entrant| entrant == player)
+            .ok_or(PV3Error::InvalidParticipant)?;
+        Ok(self.player_funding[index])
+    }
+}
+
+pub const MAX_LOBBY_PLAYERS: u8 = 8;
+pub const LOBBY_VERDICT_TAG: &[u8] = b"pv3:lobby";
+
+// Lobby functions
+#[allow(clippy::too_many_arguments)]
+pub fn create_lobby(
+    ctx: Context<CreateLobby>,
+    nonce: u64,
+    game_id: String,
+    wager_amount: u64,
+    max_players: u8,
+    payout_bps: Vec<u16>,
+    join_deadline: i64,
+    result_window: i64,
+    use_session_vault: bool,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let lobby = &mut ctx.accounts.lobby;
+    let creator = &ctx.accounts.creator;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require!(!config.is_paused, PV3Error::PlatformPaused);
+    require_wager_in_bounds(config, &ctx.accounts.game_config, wager_amount)?;
+    let wager_tier = wager_tier_index(config, wager_amount)?;
+    require!(join_deadline > current_time, PV3Error::InvalidExpiryTime);
+    require!(
+        (1..=MAX_RESULT_WINDOW).contains(&result_window),
+        PV3Error::InvalidResultWindow
+    );
+    require!(
+        (2..=MAX_LOBBY_PLAYERS).contains(&max_players),
+        PV3Error::InvalidLobbySize
+    );
+
+    // Every paid rank needs a player, and the table must distribute the whole net pot
+    require!(
+        !payout_bps.is_empty() && payout_bps.len() <= max_players as usize,
+        PV3Error::InvalidPayoutTable
+    );
+    require!(
+        payout_bps.iter().map(|bps| *bps as u32).sum::<u32>() == 10000,
+        PV3Error::InvalidPayoutTable
+    );
+
+    let creator_profile = &mut ctx.accounts.creator_profile;
+    require!(nonce == creator_profile.match_nonce, PV3Error::InvalidMatchNonce);
+    creator_profile.match_nonce += 1;
+
+    let match_number = next_match_number(&mut ctx.accounts.config);
+
+    lobby.creator = creator.key();
+    lobby.game_id = game_id.clone();
+    lobby.wager_amount = wager_amount;
+    lobby.wager_tier = wager_tier;
+    lobby.max_players = max_players;
+    lobby.players = vec![creator.key()];
+    lobby.player_funding = Vec::new(); // Filled in once the wager is funded
+    lobby.payout_bps = payout_bps.clone();
+    lobby.total_pot = wager_amount;
+    lobby.join_deadline = join_deadline;
+    lobby.result_window = result_window;
+    lobby.result_deadline = 0; // Set once the lobby starts
+    lobby.status = MatchStatus::WaitingForPlayer;
+    lobby.ranking = Vec::new();
+    lobby.result_hash = [0u8; 32];
+    lobby.settle_after = 0;
+    lobby.created_at = current_time;
+    lobby.creator_nonce = nonce;
+    lobby.match_number = match_number;
+    lobby.bump = ctx.bumps.lobby;
+
+    init_escrow(&mut ctx.accounts.lobby_escrow, lobby.key(), ctx.bumps.lobby_escrow);
+
+    let funding_source = fund_wager(
+        use_session_vault,
+        wager_amount,
+        ctx.accounts.session_vault.as_mut(),
+        creator,
+        &mut ctx.accounts.lobby_escrow,
+        &ctx.accounts.system_program,
+    )?;
+    lobby.player_funding.push(funding_source);
+
+    emit!(LobbyCreated {
+        lobby_id: lobby.key(),
+        match_number,
+        creator: creator.key(),
+        game_id,
+        wager_amount,
+        wager_tier,
+        max_players,
+        payout_bps,
+        join_deadline,
+        funding_source,
+    });
+
+    Ok(())
+}
+
+pub fn index_lobby(ctx: Context<IndexLobby>) -> Result<()> {
+    let lobby = &ctx.accounts.lobby;
+
+    init_match_index(
+        &mut ctx.accounts.match_index,
+        lobby.match_number,
+        lobby.key(),
+        ctx.bumps.match_index,
+    );
+
+    emit!(MatchIndexed {
+        match_number: lobby.match_number,
+        match_account: lobby.key(),
+    });
+
+    Ok(())
+}
+
+pub fn join_lobby(ctx: Context<JoinLobby>, use_session_vault: bool) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let lobby = &mut ctx.accounts.lobby;
+    let player = &ctx.accounts.player;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require!(!config.is_paused, PV3Error::PlatformPaused);
+    require!(
+        lobby.status == MatchStatus::WaitingForPlayer,
+        PV3Error::MatchNotAvailable
+    );
+    require!(current_time < lobby.join_deadline, PV3Error::MatchExpired);
+    require!(
+        lobby.players.len() < lobby.max_players as usize,
+        PV3Error::LobbyFull
+    );
+    require!(
+        !lobby.players.contains(&player.key()),
+        PV3Error::AlreadyInLobby
+    );
+
+    let wager_amount = lobby.wager_amount;
+
+    let funding_source = fund_wager(
+        use_session_vault,
+        wager_amount,
+        ctx.accounts.session_vault.as_mut(),
+        player,
+        &mut ctx.accounts.lobby_escrow,
+        &ctx.accounts.system_program,
+    )?;
+
+    lobby.players.push(player.key());
+    lobby.player_funding.push(funding_source);
+    lobby.total_pot += wager_amount;
+
+    emit!(LobbyJoined {
+        lobby_id: lobby.key(),
+        player: player.key(),
+        player_count: lobby.players.len() as u8,
+        total_pot: lobby.total_pot,
+        funding_source,
+    });
+
+    // A full lobby starts on its own
+    if lobby.players.len() == lobby.max_players as usize {
+        start(lobby, current_time)?;
+    }
+
+    Ok(())
+}
+
+pub fn start_lobby(ctx: Context<StartLobby>) -> Result<()> {
+    let lobby = &mut ctx.accounts.lobby;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require!(
+        lobby.status == MatchStatus::WaitingForPlayer,
+        PV3Error::MatchNotAvailable
+    );
+    // Past the join deadline the entries belong to refund_lobby
+    require!(current_time <= lobby.join_deadline, PV3Error::MatchExpired);
+    // The creator may start early as long as every paid rank can be filled
+    require!(
+        lobby.players.len() >= 2 && lobby.players.len() >= lobby.payout_bps.len(),
+        PV3Error::NotEnoughPlayers
+    );
+
+    start(lobby, current_time)
+}
+
+pub fn submit_lobby_result<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SubmitLobbyResult<'info>>,
+    ranking: Vec<Pubkey>,
+    result_hash: [u8; 32],
+    ed25519_signature: [u8; 64],
+    ed25519_instruction_index: u8,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let lobby = &mut ctx.accounts.lobby;
+
+    require_unsettled(&lobby.status)?;
+    require!(
+        lobby.status == MatchStatus::InProgress,
+        PV3Error::MatchNotInProgress
+    );
+
+    // The ranking must list every entrant exactly once
+    require!(
+        ranking.len() == lobby.players.len() &&
+            lobby.players.iter().all(|player| ranking.contains(player)),
+        PV3Error::InvalidRanking
+    );
+
+    let expected_message = create_lobby_result_message(lobby.key(), &ranking, result_hash);
+
+    verify_verifier_signature(
+        &ctx.accounts.instruction_sysvar,
+        ed25519_instruction_index,
+        &config.verifier_pubkey,
+        &ed25519_signature,
+        &expected_message,
+    )?;
+
+    lobby.ranking = ranking;
+    lobby.result_hash = result_hash;
+
+    // With a challenge period configured, hold the payout until finalize_lobby
+    if lobby.hold_for_challenge(config)? {
+        emit!(LobbyResultPending {
+            lobby_id: lobby.key(),
+            ranking: lobby.ranking.clone(),
+            settle_after: lobby.settle_after,
+        });
+
+        return Ok(());
+    }
+
+    let accounts = ctx.accounts;
+    pay_ranking(
+        &mut accounts.lobby,
+        &mut accounts.lobby_escrow,
+        &mut accounts.config,
+        &accounts.treasury,
+        &accounts.referral_pool,
+        ctx.remaining_accounts,
+    )
+}
+
+pub fn finalize_lobby<'info>(ctx: Context<'_, '_, 'info, 'info, FinalizeLobby<'info>>) -> Result<()> {
+    let lobby = &ctx.accounts.lobby;
+
+    require_finalizable(&lobby.status, lobby.settle_after)?;
+
+    let accounts = ctx.accounts;
+    pay_ranking(
+        &mut accounts.lobby,
+        &mut accounts.lobby_escrow,
+        &mut accounts.config,
+        &accounts.treasury,
+        &accounts.referral_pool,
+        ctx.remaining_accounts,
+    )
+}
+
+pub fn flag_lobby(ctx: Context<FlagLobby>) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let lobby = &mut ctx.accounts.lobby;
+    let flagger = &ctx.accounts.flagger;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require_flaggable(&lobby.status, lobby.settle_after, current_time)?;
+
+    // Any entrant or a platform admin may hold the payout
+    require!(
+        lobby.players.contains(&flagger.key()) || config.admin_signers.contains(&flagger.key()),
+        PV3Error::UnauthorizedFlag
+    );
+
+    lobby.status = MatchStatus::Flagged;
+
+    emit!(LobbyFlagged {
+        lobby_id: lobby.key(),
+        flagged_by: flagger.key(),
+        timestamp: current_time,
+    });
+
+    Ok(())
+}
+
+pub fn resolve_flagged_lobby<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ResolveFlaggedLobby<'info>>,
+    release_payout: bool,
+) -> Result<()> {
+    require!(
+        ctx.accounts.lobby.status == MatchStatus::Flagged,
+        PV3Error::MatchNotFlagged
+    );
+
+    let accounts = ctx.accounts;
+    if release_payout {
+        // Flag dismissed, pay the recorded ranking
+        pay_ranking(
+            &mut accounts.lobby,
+            &mut accounts.lobby_escrow,
+            &mut accounts.config,
+            &accounts.treasury,
+            &accounts.referral_pool,
+            ctx.remaining_accounts,
+        )?;
+    } else {
+        // Ranking voided, every entrant gets their wager back
+        refund_entrants(&mut accounts.lobby, &mut accounts.lobby_escrow, ctx.remaining_accounts)?;
+    }
+
+    emit!(FlaggedLobbyResolved {
+        lobby_id: accounts.lobby.key(),
+        payout_released: release_payout,
+        admin1: accounts.admin1.key(),
+        admin2: accounts.admin2.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn refund_lobby<'info>(ctx: Context<'_, '_, 'info, 'info, RefundLobby<'info>>) -> Result<()> {
+    let lobby = &ctx.accounts.lobby;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require_refund_expired(
+        &lobby.status,
+        lobby.join_deadline,
+        lobby.result_deadline,
+        current_time,
+    )?;
+
+    let accounts = ctx.accounts;
+    refund_entrants(&mut accounts.lobby, &mut accounts.lobby_escrow, ctx.remaining_accounts)
+}
+
+pub fn close_lobby(ctx: Context<CloseLobby>) -> Result<()> {
+    let lobby = &ctx.accounts.lobby;
+
+    require_closable(&lobby.status)?;
+
+    // Rent goes back to the creator through the `close` constraints
+    let escrow_swept = ctx.accounts.lobby_escrow.to_account_info().lamports();
+
+    close_if_initialized(&ctx.accounts.match_index, &ctx.accounts.creator)?;
+
+    emit!(MatchClosed {
+        match_id: lobby.key(),
+        creator: ctx.accounts.creator.key(),
+        rent_reclaimed: lobby.to_account_info().lamports(),
+        escrow_swept,
+    });
+
+    Ok(())
+}
+
+// Pays the recorded ranking under the payout table. Recipients are one per paid rank,
+// each the player's wallet or the session vault that funded their entry
+fn pay_ranking<'info>(
+    lobby: &mut Account<'info, Lobby>,
+    lobby_escrow: &mut Account<'info, Escrow>,
+    config: &mut Account<'info, PlatformConfig>,
+    treasury: &AccountInfo<'info>,
+    referral_pool: &AccountInfo<'info>,
+    recipients: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    let paid_ranks = lobby.payout_bps.len();
+    require!(recipients.len() == paid_ranks, PV3Error::InvalidRanking);
+
+    let winners = lobby.ranking[..paid_ranks]
+        .iter()
+        .map(|player| Ok((*player, lobby.funding_of(player)?)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let total_pot = lobby.total_pot;
+    let (platform_fee, treasury_fee, referral_fee) = calculate_fees(config, total_pot);
+
+    require!(lobby_escrow.balance >= total_pot, PV3Error::InsufficientEscrowBalance);
+
+    let payouts = pay_shares(
+        lobby_escrow,
+        total_pot - platform_fee,
+        &winners,
+        &lobby.payout_bps,
+        recipients,
+    )?;
+    release_platform_fee(lobby_escrow, treasury, referral_pool, treasury_fee, referral_fee)?;
+
+    lobby.status = MatchStatus::Completed;
+
+    record_settlement(config, total_pot);
+
+    emit!(LobbyCompleted {
+        lobby_id: lobby.key(),
+        wager_tier: lobby.wager_tier,
+        winners: lobby.ranking[..paid_ranks].to_vec(),
+        payouts,
+        platform_fee,
+        treasury_fee,
+        referral_fee,
+    });
+
+    Ok(())
+}
+
+// Returns every entrant's wager to its funding source, targets in join order
+fn refund_entrants<'info>(
+    lobby: &mut Account<'info, Lobby>,
+    lobby_escrow: &mut Account<'info, Escrow>,
+    targets: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    let entries: Vec<(Pubkey, FundingSource)> = lobby.players
+        .iter()
+        .copied()
+        .zip(lobby.player_funding.iter().copied())
+        .collect();
+
+    let wager_amount = lobby.wager_amount;
+    refund_entries(lobby_escrow, &entries, targets, wager_amount)?;
+
+    lobby.status = MatchStatus::Refunded;
+
+    emit!(LobbyRefunded {
+        lobby_id: lobby.key(),
+        player_count: lobby.players.len() as u8,
+        refund_amount: wager_amount,
+    });
+
+    Ok(())
+}
+
+fn start(lobby: &mut Account<Lobby>, current_time: i64) -> Result<()> {
+    lobby.status = MatchStatus::InProgress;
+    lobby.result_deadline = current_time + lobby.result_window;
+
+    emit!(LobbyStarted {
+        lobby_id: lobby.key(),
+        player_count: lobby.players.len() as u8,
+        total_pot: lobby.total_pot,
+        result_deadline: lobby.result_deadline,
+    });
+
+    Ok(())
+}
+
+// Lobby verdicts commit to the full ranking, first place first
+pub fn create_lobby_result_message(
+    lobby_id: Pubkey,
+    ranking: &[Pubkey],
+    result_hash: [u8; 32],
+) -> Vec<u8> {
+    let mut message = Vec::new();
+    message.extend_from_slice(LOBBY_VERDICT_TAG);
+    message.extend_from_slice(&lobby_id.to_bytes());
+    for player in ranking {
+        message.extend_from_slice(&player.to_bytes());
+    }
+    message.extend_from_slice(&result_hash);
+    message
+}
+
+// Events
+#[event]
+pub struct LobbyCreated {
+    pub lobby_id: Pubkey,
+    pub match_number: u64,
+    pub creator: Pubkey,
+    pub game_id: String,
+    pub wager_amount: u64,
+    pub wager_tier: Option<u8>,
+    pub max_players: u8,
+    pub payout_bps: Vec<u16>,
+    pub join_deadline: i64,
+    pub funding_source: FundingSource,
+}
+
+#[event]
+pub struct LobbyJoined {
+    pub lobby_id: Pubkey,
+    pub player: Pubkey,
+    pub player_count: u8,
+    pub total_pot: u64,
+    pub funding_source: FundingSource,
+}
+
+#[event]
+pub struct LobbyStarted {
+    pub lobby_id: Pubkey,
+    pub player_count: u8,
+    pub total_pot: u64,
+    pub result_deadline: i64,
+}
+
+#[event]
+pub struct LobbyCompleted {
+    pub lobby_id: Pubkey,
+    pub wager_tier: Option<u8>,
+    pub winners: Vec<Pubkey>, // Paid ranks, first place first
+    pub payouts: Vec<u64>,
+    pub platform_fee: u64,
+    pub treasury_fee: u64,
+    pub referral_fee: u64,
+}
+
+#[event]
+pub struct LobbyResultPending {
+    pub lobby_id: Pubkey,
+    pub ranking: Vec<Pubkey>,
+    pub settle_after: i64,
+}
+
+#[event]
+pub struct LobbyFlagged {
+    pub lobby_id: Pubkey,
+    pub flagged_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FlaggedLobbyResolved {
+    pub lobby_id: Pubkey,
+    pub payout_released: bool,
+    pub admin1: Pubkey,
+    pub admin2: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LobbyRefunded {
+    pub lobby_id: Pubkey,
+    pub player_count: u8,
+    pub refund_amount: u64, // per player
+}
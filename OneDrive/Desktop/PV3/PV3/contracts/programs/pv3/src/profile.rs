@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+/// Player Profile - Per-wallet state that outlives individual matches. Holds the
+/// creator nonce used to seed match PDAs so clients can derive them up front
+#[derive(Accounts)]
+pub struct CreateProfile<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + PlayerProfile::INIT_SPACE,
+        seeds = [b"profile", user.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PlayerProfile {
+    pub owner: Pubkey,
+    pub match_nonce: u64, // Next nonce for ["match", owner, nonce] PDAs
+    pub abandonments: u32, // Matches lost to a signed abandonment verdict
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+// Profile management functions
+pub fn create_profile(ctx: Context<CreateProfile>) -> Result<()> {
+    let profile = &mut ctx.accounts.profile;
+    let user = &ctx.accounts.user;
+    
+    profile.owner = user.key();
+    profile.match_nonce = 0;
+    profile.abandonments = 0;
+    profile.created_at = Clock::get()?.unix_timestamp;
+    profile.bump = ctx.bumps.profile;
+    
+    emit!(ProfileCreated {
+        user: user.key(),
+        profile: profile.key(),
+    });
+    
+    Ok(())
+}
+
+// Events
+#[event]
+pub struct ProfileCreated {
+    pub user: Pubkey,
+    pub profile: Pubkey,
+}
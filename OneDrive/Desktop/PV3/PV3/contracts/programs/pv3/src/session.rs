@@ -49,23 +49,6 @@ pub struct WithdrawFromSession<'info> {
     pub system_program: Program<'info, System>,
 }
 
-#[derive(Accounts)]
-pub struct UseSessionFunds<'info> {
-    #[account(
-        mut,
-        seeds = [b"session", user.key().as_ref()],
-        bump = session_vault.bump,
-        constraint = session_vault.balance >= amount @ PV3Error::InsufficientSessionBalance
-    )]
-    pub session_vault: Account<'info, SessionVault>,
-    
-    #[account(mut)]
-    /// CHECK: Destination account (match escrow)
-    pub destination: AccountInfo<'info>,
-    
-    pub user: Signer<'info>,
-}
-
 #[account]
 #[derive(InitSpace)]
 pub struct SessionVault {
@@ -141,14 +124,8 @@ pub fn withdraw_from_session(ctx: Context<WithdrawFromSession>, amount: u64) ->
     require!(amount > 0, PV3Error::InvalidAmount);
     require!(session_vault.balance >= amount, PV3Error::InsufficientSessionBalance);
     
-    // Transfer SOL from session vault PDA to user
-    let seeds = &[
-        b"session",
-        user.key().as_ref(),
-        &[session_vault.bump],
-    ];
-    let signer_seeds = &[&seeds[..]];
-    
+    // Transfer SOL from session vault PDA to user. The vault is program-owned,
+    // so its lamports are moved directly instead of through a signed transfer
     **session_vault.to_account_info().try_borrow_mut_lamports()? -= amount;
     **user.to_account_info().try_borrow_mut_lamports()? += amount;
     
@@ -165,34 +142,6 @@ pub fn withdraw_from_session(ctx: Context<WithdrawFromSession>, amount: u64) ->
     Ok(())
 }
 
-pub fn use_session_funds(
-    ctx: Context<UseSessionFunds>, 
-    amount: u64
-) -> Result<()> {
-    let session_vault = &mut ctx.accounts.session_vault;
-    let user = &ctx.accounts.user;
-    
-    require!(amount > 0, PV3Error::InvalidAmount);
-    require!(session_vault.balance >= amount, PV3Error::InsufficientSessionBalance);
-    
-    // Transfer from session vault to destination (usually match escrow)
-    let seeds = &[
-        b"session",
-        user.key().as_ref(),
-        &[session_vault.bump],
-    ];
-    let _signer_seeds = &[&seeds[..]];
-    
-    **session_vault.to_account_info().try_borrow_mut_lamports()? -= amount;
-    **ctx.accounts.destination.to_account_info().try_borrow_mut_lamports()? += amount;
-    
-    session_vault.balance -= amount;
-    session_vault.matches_played += 1;
-    session_vault.last_activity = Clock::get()?.unix_timestamp;
-    
-    Ok(())
-}
-
 // Events
 #[event]
 pub struct SessionCreated {
@@ -0,0 +1,833 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{hash::hash, sysvar::instructions};
+use crate::{
+    calculate_fees, close_if_initialized, fund_wager, hold_for_challenge, init_escrow,
+    init_match_index, next_match_number, pay_shares, record_settlement, refund_entries,
+    release_platform_fee, require_closable, require_finalizable, require_flaggable,
+    require_refund_expired, require_unsettled, require_wager_in_bounds,
+    verify_verifier_signature, wager_tier_index, Escrow, FundingSource, MatchClosed, MatchIndex,
+    MatchIndexed, MatchStatus, PlatformConfig, PlayerProfile, PV3Error, SessionVault,
+    MAX_RESULT_WINDOW,
+};
+
+/// Team Match Manager - 2v2 matches where every member funds their own share
+/// and the winning team splits the net pot evenly
+#[derive(Accounts)]
+#[instruction(nonce: u64, game_id: String)]
+pub struct CreateTeamMatch<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + TeamMatch::INIT_SPACE,
+        seeds = [b"team_match", creator.key().as_ref(), &nonce.to_le_bytes()],
+        bump
+    )]
+    pub team_match: Account<'info, TeamMatch>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", creator.key().as_ref()],
+        bump = creator_profile.bump
+    )]
+    pub creator_profile: Account<'info, PlayerProfile>,
+
+    #[account(
+        seeds = [b"game_config", hash(game_id.as_bytes()).as_ref()],
+        bump
+    )]
+    /// CHECK: Optional per-game wager bounds, may be uninitialized
+    pub game_config: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Escrow::INIT_SPACE,
+        seeds = [b"escrow", team_match.key().as_ref()],
+        bump
+    )]
+    pub team_escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"session", creator.key().as_ref()],
+        bump
+    )]
+    pub session_vault: Option<Account<'info, SessionVault>>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Indexing is a separate step so creates never race for the next number's address
+#[derive(Accounts)]
+pub struct IndexTeamMatch<'info> {
+    #[account(has_one = creator @ PV3Error::NotMatchCreator)]
+    pub team_match: Account<'info, TeamMatch>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + MatchIndex::INIT_SPACE,
+        seeds = [b"match_index", team_match.match_number.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub match_index: Account<'info, MatchIndex>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinTeamMatch<'info> {
+    #[account(mut)]
+    pub team_match: Account<'info, TeamMatch>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", team_match.key().as_ref()],
+        bump = team_escrow.bump
+    )]
+    pub team_escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"session", player.key().as_ref()],
+        bump
+    )]
+    pub session_vault: Option<Account<'info, SessionVault>>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitTeamResult<'info> {
+    #[account(mut)]
+    pub team_match: Account<'info, TeamMatch>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", team_match.key().as_ref()],
+        bump = team_escrow.bump
+    )]
+    pub team_escrow: Account<'info, Escrow>,
+
+    #[account(mut, address = config.treasury)]
+    /// CHECK: Treasury account for platform fees
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, address = config.referral_pool)]
+    /// CHECK: Referral pool account
+    pub referral_pool: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+
+    /// CHECK: This is the instructions sysvar
+    #[account(address = instructions::ID)]
+    pub instruction_sysvar: AccountInfo<'info>,
+    // remaining_accounts: the winning team's members in join order. Each is the
+    // member's wallet, or their session vault if that funded their share
+}
+
+#[derive(Accounts)]
+pub struct FinalizeTeamMatch<'info> {
+    #[account(mut)]
+    pub team_match: Account<'info, TeamMatch>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", team_match.key().as_ref()],
+        bump = team_escrow.bump
+    )]
+    pub team_escrow: Account<'info, Escrow>,
+
+    #[account(mut, address = config.treasury)]
+    /// CHECK: Treasury account for platform fees
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, address = config.referral_pool)]
+    /// CHECK: Referral pool account
+    pub referral_pool: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+    // remaining_accounts: the winning team's payout targets, as for submit_team_result
+}
+
+#[derive(Accounts)]
+pub struct FlagTeamMatch<'info> {
+    #[account(mut)]
+    pub team_match: Account<'info, TeamMatch>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, PlatformConfig>,
+
+    pub flagger: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveFlaggedTeamMatch<'info> {
+    #[account(mut)]
+    pub team_match: Account<'info, TeamMatch>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", team_match.key().as_ref()],
+        bump = team_escrow.bump
+    )]
+    pub team_escrow: Account<'info, Escrow>,
+
+    #[account(mut, address = config.treasury)]
+    /// CHECK: Treasury account for platform fees
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, address = config.referral_pool)]
+    /// CHECK: Referral pool account
+    pub referral_pool: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin_signers.contains(&admin1.key()) @ PV3Error::UnauthorizedAdmin,
+        constraint = config.admin_signers.contains(&admin2.key()) @ PV3Error::UnauthorizedAdmin,
+        constraint = admin1.key() != admin2.key() @ PV3Error::InsufficientAdminSignatures
+    )]
+    pub config: Account<'info, PlatformConfig>,
+
+    // 2-of-3 multisig requirement
+    pub admin1: Signer<'info>,
+    pub admin2: Signer<'info>,
+    // remaining_accounts: the winning team's payout targets when releasing the payout,
+    // otherwise every member's refund target, team A then team B
+}
+
+#[derive(Accounts)]
+pub struct RefundTeamMatch<'info> {
+    #[account(mut)]
+    pub team_match: Account<'info, TeamMatch>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", team_match.key().as_ref()],
+        bump = team_escrow.bump
+    )]
+    pub team_escrow: Account<'info, Escrow>,
+    // remaining_accounts: team A members then team B members, in join order, each
+    // as their wallet or funding session vault
+}
+
+#[derive(Accounts)]
+pub struct CloseTeamMatch<'info> {
+    #[account(
+        mut,
+        has_one = creator @ PV3Error::NotMatchCreator,
+        close = creator
+    )]
+    pub team_match: Account<'info, TeamMatch>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", team_match.key().as_ref()],
+        bump = team_escrow.bump,
+        close = creator
+    )]
+    pub team_escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"match_index", team_match.match_number.to_le_bytes().as_ref()],
+        bump
+    )]
+    /// CHECK: Index entry for the team match, closed only if it was created
+    pub match_index: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct TeamMatch {
+    pub creator: Pubkey,
+    #[max_len(50)]
+    pub game_id: String,
+    pub wager_amount: u64,          // Per player share
+    pub wager_tier: Option<u8>,
+    #[max_len(2)]
+    pub team_a: Vec<Pubkey>,        // Creator's side
+    #[max_len(2)]
+    pub team_b: Vec<Pubkey>,
+    #[max_len(2)]
+    pub team_a_funding: Vec<FundingSource>, // Where each member's share came from, same order
+    #[max_len(2)]
+    pub team_b_funding: Vec<FundingSource>,
+    pub total_pot: u64,
+    pub join_deadline: i64,
+    pub result_window: i64,
+    pub result_deadline: i64,
+    pub status: MatchStatus,
+    pub winning_team: Option<TeamSide>,
+    pub result_hash: [u8; 32],
+    pub settle_after: i64,          // End of the challenge window for a pending verdict
+    pub created_at: i64,
+    pub creator_nonce: u64,
+    pub match_number: u64,          // Shares the global sequence with 1v1 matches
+    pub bump: u8,
+}
+
+impl TeamMatch {
+    pub fn hold_for_challenge(&mut self, config: &PlatformConfig) -> Result<bool> {
+        hold_for_challenge(config, &mut self.status, &mut self.settle_after)
+    }
+
+    pub fn team(&self, side: TeamSide) -> &Vec<Pubkey> {
+        match side {
+            TeamSide::A => &self.team_a,
+            TeamSide::B => &self.team_b,
+        }
+    }
+
+    pub fn team_funding(&self, side: TeamSide) -> &Vec<FundingSource> {
+        match side {
+            TeamSide::A => &self.team_a_funding,
+            TeamSide::B => &self.team_b_funding,
+        }
+    }
+
+    // The side's members paired with where each one's share came from
+    pub fn members(&self, side: TeamSide) -> Vec<(Pubkey, FundingSource)> {
+        self.team(side)
+            .iter()
+            .copied()
+            .zip(self.team_funding(side).iter().copied())
+            .collect()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum TeamSide {
+    A,
+    B,
+}
+
+pub const TEAM_SIZE: usize = 2;
+pub const EVEN_SPLIT_BPS: [u16; TEAM_SIZE] = [5000, 5000];
+pub const TEAM_VERDICT_TAG: &[u8] = b"pv3:team";
+
+// Team match functions
+pub fn create_team_match(
+    ctx: Context<CreateTeamMatch>,
+    nonce: u64,
+    game_id: String,
+    wager_amount: u64,
+    join_deadline: i64,
+    result_window: i64,
+    use_session_vault: bool,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let team_match = &mut ctx.accounts.team_match;
+    let creator = &ctx.accounts.creator;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require!(!config.is_paused, PV3Error::PlatformPaused);
+    require_wager_in_bounds(config, &ctx.accounts.game_config, wager_amount)?;
+    let wager_tier = wager_tier_index(config, wager_amount)?;
+    require!(join_deadline > current_time, PV3Error::InvalidExpiryTime);
+    require!(
+        (1..=MAX_RESULT_WINDOW).contains(&result_window),
+        PV3Error::InvalidResultWindow
+    );
+
+    let creator_profile = &mut ctx.accounts.creator_profile;
+    require!(nonce == creator_profile.match_nonce, PV3Error::InvalidMatchNonce);
+    creator_profile.match_nonce += 1;
+
+    let match_number = next_match_number(&mut ctx.accounts.config);
+
+    team_match.creator = creator.key();
+    team_match.game_id = game_id.clone();
+    team_match.wager_amount = wager_amount;
+    team_match.wager_tier = wager_tier;
+    team_match.team_a = vec![creator.key()];
+    team_match.team_b = Vec::new();
+    team_match.team_a_funding = Vec::new(); // Filled in once the wager is funded
+    team_match.team_b_funding = Vec::new();
+    team_match.total_pot = wager_amount;
+    team_match.join_deadline = join_deadline;
+    team_match.result_window = result_window;
+    team_match.result_deadline = 0; // Set once both teams are full
+    team_match.status = MatchStatus::WaitingForPlayer;
+    team_match.winning_team = None;
+    team_match.result_hash = [0u8; 32];
+    team_match.settle_after = 0;
+    team_match.created_at = current_time;
+    team_match.creator_nonce = nonce;
+    team_match.match_number = match_number;
+    team_match.bump = ctx.bumps.team_match;
+
+    init_escrow(&mut ctx.accounts.team_escrow, team_match.key(), ctx.bumps.team_escrow);
+
+    let funding_source = fund_wager(
+        use_session_vault,
+        wager_amount,
+        ctx.accounts.session_vault.as_mut(),
+        creator,
+        &mut ctx.accounts.team_escrow,
+        &ctx.accounts.system_program,
+    )?;
+    team_match.team_a_funding.push(funding_source);
+
+    emit!(TeamMatchCreated {
+        match_id: team_match.key(),
+        match_number,
+        creator: creator.key(),
+        game_id,
+        wager_amount,
+        wager_tier,
+        join_deadline,
+        result_window,
+        funding_source,
+    });
+
+    Ok(())
+}
+
+pub fn index_team_match(ctx: Context<IndexTeamMatch>) -> Result<()> {
+    let team_match = &ctx.accounts.team_match;
+
+    init_match_index(
+        &mut ctx.accounts.match_index,
+        team_match.match_number,
+        team_match.key(),
+        ctx.bumps.match_index,
+    );
+
+    emit!(MatchIndexed {
+        match_number: team_match.match_number,
+        match_account: team_match.key(),
+    });
+
+    Ok(())
+}
+
+pub fn join_team_match(
+    ctx: Context<JoinTeamMatch>,
+    side: TeamSide,
+    use_session_vault: bool,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let team_match = &mut ctx.accounts.team_match;
+    let player = &ctx.accounts.player;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require!(!config.is_paused, PV3Error::PlatformPaused);
+    require!(
+        team_match.status == MatchStatus::WaitingForPlayer,
+        PV3Error::MatchNotAvailable
+    );
+    require!(current_time < team_match.join_deadline, PV3Error::MatchExpired);
+    require!(
+        !team_match.team_a.contains(&player.key()) && !team_match.team_b.contains(&player.key()),
+        PV3Error::AlreadyOnTeam
+    );
+    require!(team_match.team(side).len() < TEAM_SIZE, PV3Error::TeamFull);
+
+    let wager_amount = team_match.wager_amount;
+
+    let funding_source = fund_wager(
+        use_session_vault,
+        wager_amount,
+        ctx.accounts.session_vault.as_mut(),
+        player,
+        &mut ctx.accounts.team_escrow,
+        &ctx.accounts.system_program,
+    )?;
+
+    match side {
+        TeamSide::A => {
+            team_match.team_a.push(player.key());
+            team_match.team_a_funding.push(funding_source);
+        }
+        TeamSide::B => {
+            team_match.team_b.push(player.key());
+            team_match.team_b_funding.push(funding_source);
+        }
+    }
+    team_match.total_pot += wager_amount;
+
+    // The match starts once both sides are full
+    let is_full = team_match.team_a.len() == TEAM_SIZE && team_match.team_b.len() == TEAM_SIZE;
+    if is_full {
+        team_match.status = MatchStatus::InProgress;
+        team_match.result_deadline = current_time + team_match.result_window;
+    }
+
+    emit!(TeamMatchJoined {
+        match_id: team_match.key(),
+        player: player.key(),
+        side,
+        total_pot: team_match.total_pot,
+        started: is_full,
+        result_deadline: team_match.result_deadline,
+        funding_source,
+    });
+
+    Ok(())
+}
+
+pub fn submit_team_result<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SubmitTeamResult<'info>>,
+    winning_team: TeamSide,
+    result_hash: [u8; 32],
+    ed25519_signature: [u8; 64],
+    ed25519_instruction_index: u8,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let team_match = &mut ctx.accounts.team_match;
+
+    require_unsettled(&team_match.status)?;
+    require!(
+        team_match.status == MatchStatus::InProgress,
+        PV3Error::MatchNotInProgress
+    );
+
+    let expected_message = create_team_result_message(team_match.key(), winning_team, result_hash);
+
+    verify_verifier_signature(
+        &ctx.accounts.instruction_sysvar,
+        ed25519_instruction_index,
+        &config.verifier_pubkey,
+        &ed25519_signature,
+        &expected_message,
+    )?;
+
+    team_match.winning_team = Some(winning_team);
+    team_match.result_hash = result_hash;
+
+    // With a challenge period configured, hold the payout until finalize_team_match
+    if team_match.hold_for_challenge(config)? {
+        emit!(TeamMatchResultPending {
+            match_id: team_match.key(),
+            winning_team,
+            settle_after: team_match.settle_after,
+        });
+
+        return Ok(());
+    }
+
+    let accounts = ctx.accounts;
+    pay_winning_team(
+        &mut accounts.team_match,
+        &mut accounts.team_escrow,
+        &mut accounts.config,
+        &accounts.treasury,
+        &accounts.referral_pool,
+        ctx.remaining_accounts,
+    )
+}
+
+pub fn finalize_team_match<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FinalizeTeamMatch<'info>>,
+) -> Result<()> {
+    let team_match = &ctx.accounts.team_match;
+
+    require_finalizable(&team_match.status, team_match.settle_after)?;
+
+    let accounts = ctx.accounts;
+    pay_winning_team(
+        &mut accounts.team_match,
+        &mut accounts.team_escrow,
+        &mut accounts.config,
+        &accounts.treasury,
+        &accounts.referral_pool,
+        ctx.remaining_accounts,
+    )
+}
+
+pub fn flag_team_match(ctx: Context<FlagTeamMatch>) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let team_match = &mut ctx.accounts.team_match;
+    let flagger = &ctx.accounts.flagger;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require_flaggable(&team_match.status, team_match.settle_after, current_time)?;
+
+    // Only a member of the losing team or a platform admin may hold the payout
+    let losing_team = match team_match.winning_team {
+        Some(TeamSide::A) => TeamSide::B,
+        _ => TeamSide::A,
+    };
+    require!(
+        team_match.team(losing_team).contains(&flagger.key()) ||
+            config.admin_signers.contains(&flagger.key()),
+        PV3Error::UnauthorizedFlag
+    );
+
+    team_match.status = MatchStatus::Flagged;
+
+    emit!(TeamMatchFlagged {
+        match_id: team_match.key(),
+        flagged_by: flagger.key(),
+        timestamp: current_time,
+    });
+
+    Ok(())
+}
+
+pub fn resolve_flagged_team_match<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ResolveFlaggedTeamMatch<'info>>,
+    release_payout: bool,
+) -> Result<()> {
+    require!(
+        ctx.accounts.team_match.status == MatchStatus::Flagged,
+        PV3Error::MatchNotFlagged
+    );
+
+    let accounts = ctx.accounts;
+    if release_payout {
+        // Flag dismissed, pay the recorded winning team
+        pay_winning_team(
+            &mut accounts.team_match,
+            &mut accounts.team_escrow,
+            &mut accounts.config,
+            &accounts.treasury,
+            &accounts.referral_pool,
+            ctx.remaining_accounts,
+        )?;
+    } else {
+        // Verdict voided, every member gets their share back
+        refund_members(&mut accounts.team_match, &mut accounts.team_escrow, ctx.remaining_accounts)?;
+    }
+
+    emit!(FlaggedTeamMatchResolved {
+        match_id: accounts.team_match.key(),
+        payout_released: release_payout,
+        admin1: accounts.admin1.key(),
+        admin2: accounts.admin2.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn refund_team_match<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RefundTeamMatch<'info>>,
+) -> Result<()> {
+    let team_match = &ctx.accounts.team_match;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require_refund_expired(
+        &team_match.status,
+        team_match.join_deadline,
+        team_match.result_deadline,
+        current_time,
+    )?;
+
+    let accounts = ctx.accounts;
+    refund_members(&mut accounts.team_match, &mut accounts.team_escrow, ctx.remaining_accounts)
+}
+
+pub fn close_team_match(ctx: Context<CloseTeamMatch>) -> Result<()> {
+    let team_match = &ctx.accounts.team_match;
+
+    require_closable(&team_match.status)?;
+
+    // Rent goes back to the creator through the `close` constraints
+    let escrow_swept = ctx.accounts.team_escrow.to_account_info().lamports();
+
+    close_if_initialized(&ctx.accounts.match_index, &ctx.accounts.creator)?;
+
+    emit!(MatchClosed {
+        match_id: team_match.key(),
+        creator: ctx.accounts.creator.key(),
+        rent_reclaimed: team_match.to_account_info().lamports(),
+        escrow_swept,
+    });
+
+    Ok(())
+}
+
+// Splits the net pot evenly over the recorded winning team. Recipients are its members
+// in join order, each their wallet or the session vault that funded their share
+fn pay_winning_team<'info>(
+    team_match: &mut Account<'info, TeamMatch>,
+    team_escrow: &mut Account<'info, Escrow>,
+    config: &mut Account<'info, PlatformConfig>,
+    treasury: &AccountInfo<'info>,
+    referral_pool: &AccountInfo<'info>,
+    recipients: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    let winning_team = team_match.winning_team.ok_or(PV3Error::InvalidWinner)?;
+
+    // Both sides are full once in progress, so the winning side always has two members
+    require!(recipients.len() == TEAM_SIZE, PV3Error::InvalidWinner);
+    let winners = team_match.members(winning_team);
+
+    let total_pot = team_match.total_pot;
+    let (platform_fee, treasury_fee, referral_fee) = calculate_fees(config, total_pot);
+
+    require!(team_escrow.balance >= total_pot, PV3Error::InsufficientEscrowBalance);
+
+    // Even split; the first member takes the odd lamport if there is one
+    let shares = pay_shares(
+        team_escrow,
+        total_pot - platform_fee,
+        &winners,
+        &EVEN_SPLIT_BPS,
+        recipients,
+    )?;
+    release_platform_fee(team_escrow, treasury, referral_pool, treasury_fee, referral_fee)?;
+
+    team_match.status = MatchStatus::Completed;
+
+    record_settlement(config, total_pot);
+
+    emit!(TeamMatchCompleted {
+        match_id: team_match.key(),
+        wager_tier: team_match.wager_tier,
+        winning_team,
+        winners: [winners[0].0, winners[1].0],
+        share_amounts: [shares[0], shares[1]],
+        platform_fee,
+        treasury_fee,
+        referral_fee,
+    });
+
+    Ok(())
+}
+
+// Returns every member's share to its funding source, targets team A then team B
+fn refund_members<'info>(
+    team_match: &mut Account<'info, TeamMatch>,
+    team_escrow: &mut Account<'info, Escrow>,
+    targets: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    let mut members = team_match.members(TeamSide::A);
+    members.extend(team_match.members(TeamSide::B));
+
+    let wager_amount = team_match.wager_amount;
+    refund_entries(team_escrow, &members, targets, wager_amount)?;
+
+    team_match.status = MatchStatus::Refunded;
+
+    emit!(TeamMatchRefunded {
+        match_id: team_match.key(),
+        player_count: members.len() as u8,
+        refund_amount: wager_amount,
+    });
+
+    Ok(())
+}
+
+// Team verdicts name the winning side rather than a wallet
+pub fn create_team_result_message(
+    match_id: Pubkey,
+    winning_team: TeamSide,
+    result_hash: [u8; 32],
+) -> Vec<u8> {
+    let mut message = Vec::new();
+    message.extend_from_slice(TEAM_VERDICT_TAG);
+    message.extend_from_slice(&match_id.to_bytes());
+    message.push(winning_team as u8);
+    message.extend_from_slice(&result_hash);
+    message
+}
+
+// Events
+#[event]
+pub struct TeamMatchCreated {
+    pub match_id: Pubkey,
+    pub match_number: u64,
+    pub creator: Pubkey,
+    pub game_id: String,
+    pub wager_amount: u64,
+    pub wager_tier: Option<u8>,
+    pub join_deadline: i64,
+    pub result_window: i64,
+    pub funding_source: FundingSource,
+}
+
+#[event]
+pub struct TeamMatchJoined {
+    pub match_id: Pubkey,
+    pub player: Pubkey,
+    pub side: TeamSide,
+    pub total_pot: u64,
+    pub started: bool,
+    pub result_deadline: i64,
+    pub funding_source: FundingSource,
+}
+
+#[event]
+pub struct TeamMatchResultPending {
+    pub match_id: Pubkey,
+    pub winning_team: TeamSide,
+    pub settle_after: i64,
+}
+
+#[event]
+pub struct TeamMatchFlagged {
+    pub match_id: Pubkey,
+    pub flagged_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FlaggedTeamMatchResolved {
+    pub match_id: Pubkey,
+    pub payout_released: bool,
+    pub admin1: Pubkey,
+    pub admin2: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TeamMatchCompleted {
+    pub match_id: Pubkey,
+    pub wager_tier: Option<u8>,
+    pub winning_team: TeamSide,
+    pub winners: [Pubkey; 2],
+    pub share_amounts: [u64; 2],
+    pub platform_fee: u64,
+    pub treasury_fee: u64,
+    pub referral_fee: u64,
+}
+
+#[event]
+pub struct TeamMatchRefunded {
+    pub match_id: Pubkey,
+    pub player_count: u8,
+    pub refund_amount: u64, // per player
+}
@@ -0,0 +1,232 @@
+use super::*;
+use crate::{
+    DisputeResolution, FeePolicy, MatchAbandoned, MatchCompleted, MatchResultPending, MatchStatus,
+    PlayerProfile,
+};
+
+const RESULT_HASH: [u8; 32] = [5; 32];
+
+fn submit_abandonment(
+    platform: &Platform,
+    match_account: &Pubkey,
+    abandoner: &Pubkey,
+    winner: &Pubkey,
+) -> Vec<Instruction> {
+    let message = crate::create_abandonment_message(*match_account, *abandoner, RESULT_HASH);
+    let (signature, ed25519) = signed_by_verifier(platform, &message);
+    let submit = ix(
+        crate::accounts::SubmitAbandonment {
+            match_account: *match_account,
+            match_escrow: escrow_pda(match_account),
+            winner: *winner,
+            abandoner_profile: profile_pda(abandoner),
+            treasury: platform.treasury,
+            referral_pool: platform.referral_pool,
+            config: config_pda(),
+            instruction_sysvar: anchor_lang::solana_program::sysvar::instructions::ID,
+            payer: platform.verifier,
+            system_program: system_program::ID,
+        },
+        crate::instruction::SubmitAbandonment {
+            abandoner: *abandoner,
+            result_hash: RESULT_HASH,
+            ed25519_signature: signature,
+            ed25519_instruction_index: 1,
+        },
+    );
+    vec![submit, ed25519]
+}
+
+fn waive_abandonment_fee(env: &mut TestEnv, platform: &Platform) {
+    env.send(&[ix(
+        crate::accounts::EmergencyAction {
+            config: config_pda(),
+            signer: platform.admins[0],
+        },
+        crate::instruction::UpdateAbandonmentFeePolicy {
+            abandonment_fee_policy: FeePolicy::NoFee,
+        },
+    )])
+    .unwrap();
+}
+
+#[test]
+fn remaining_player_takes_the_pot_and_the_abandonment_is_counted() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, joiner) = started_match(&mut env);
+    let balance_before = env.lamports(&creator);
+
+    env.send(&submit_abandonment(&platform, &match_account, &joiner, &creator)).unwrap();
+
+    let abandoned = &env.events::<MatchAbandoned>()[0];
+    assert_eq!(abandoned.abandonments, 1);
+    assert_eq!(env.lamports(&creator), balance_before + abandoned.winner_amount);
+    assert!(abandoned.platform_fee > 0);
+    assert_eq!(env.get::<PlayerProfile>(&profile_pda(&joiner)).abandonments, 1);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Completed);
+}
+
+#[test]
+fn abandoner_without_a_profile_gets_one_opened() {
+    let (mut env, platform) = setup();
+    let creator = player(&mut env);
+    let joiner = env.wallet(100 * SOL);
+    let match_account = create_match(&mut env, &creator, &MatchOptions::default());
+    env.send(&[join_match_ix(&match_account, &joiner, false, None)]).unwrap();
+    assert!(!env.exists(&profile_pda(&joiner)));
+
+    env.send(&submit_abandonment(&platform, &match_account, &joiner, &creator)).unwrap();
+
+    let profile = env.get::<PlayerProfile>(&profile_pda(&joiner));
+    assert_eq!(profile.owner, joiner);
+    assert_eq!(profile.abandonments, 1);
+    assert_eq!(env.events::<MatchAbandoned>()[0].abandonments, 1);
+}
+
+#[test]
+fn abandoner_must_be_a_participant() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, _) = started_match(&mut env);
+    let stranger = player(&mut env);
+
+    let result = env.send(&submit_abandonment(&platform, &match_account, &stranger, &creator));
+
+    assert_eq!(result, Err(program_error(PV3Error::InvalidParticipant)));
+}
+
+#[test]
+fn pot_cannot_go_to_the_abandoner() {
+    let (mut env, platform) = setup();
+    let (match_account, _, joiner) = started_match(&mut env);
+
+    let result = env.send(&submit_abandonment(&platform, &match_account, &joiner, &joiner));
+
+    assert_eq!(result, Err(program_error(PV3Error::InvalidWinner)));
+}
+
+#[test]
+fn waived_fee_pays_the_whole_pot() {
+    let (mut env, platform) = setup();
+    waive_abandonment_fee(&mut env, &platform);
+    let (match_account, creator, joiner) = started_match(&mut env);
+    let balance_before = env.lamports(&joiner);
+
+    env.send(&submit_abandonment(&platform, &match_account, &creator, &joiner)).unwrap();
+
+    let abandoned = &env.events::<MatchAbandoned>()[0];
+    assert_eq!(abandoned.platform_fee, 0);
+    assert_eq!(abandoned.winner_amount, 2 * WAGER);
+    assert_eq!(env.lamports(&joiner), balance_before + 2 * WAGER);
+}
+
+#[test]
+fn abandonment_waits_out_the_challenge_period_under_its_fee_policy() {
+    let (mut env, platform) = setup();
+    waive_abandonment_fee(&mut env, &platform);
+    enable_challenge_period(&mut env, &platform);
+    let (match_account, creator, joiner) = started_match(&mut env);
+    let balance_before = env.lamports(&creator);
+
+    env.send(&submit_abandonment(&platform, &match_account, &joiner, &creator)).unwrap();
+
+    let pending = &env.events::<MatchResultPending>()[0];
+    assert_eq!(pending.winner, creator);
+    let state = match_state(&env, &match_account);
+    assert!(state.status == MatchStatus::PendingSettlement);
+    assert_eq!(state.abandoned_by, joiner);
+    assert_eq!(env.lamports(&creator), balance_before);
+    assert_eq!(env.get::<PlayerProfile>(&profile_pda(&joiner)).abandonments, 0);
+
+    let result = env.send(&[finalize_match_ix(&env, &platform, &match_account)]);
+    assert_eq!(result, Err(program_error(PV3Error::ChallengePeriodActive)));
+
+    env.warp(CHALLENGE_PERIOD);
+    env.send(&[finalize_match_ix(&env, &platform, &match_account)]).unwrap();
+
+    let abandoned = &env.events::<MatchAbandoned>()[0];
+    assert_eq!((abandoned.platform_fee, abandoned.abandonments), (0, 1));
+    assert_eq!(env.lamports(&creator), balance_before + 2 * WAGER);
+    assert_eq!(env.get::<PlayerProfile>(&profile_pda(&joiner)).abandonments, 1);
+}
+
+#[test]
+fn pending_abandonment_needs_the_abandoners_profile_to_pay_out() {
+    let (mut env, platform) = setup();
+    enable_challenge_period(&mut env, &platform);
+    let (match_account, creator, joiner) = started_match(&mut env);
+    env.send(&submit_abandonment(&platform, &match_account, &joiner, &creator)).unwrap();
+    env.warp(CHALLENGE_PERIOD);
+    let mut instruction = finalize_match_ix(&env, &platform, &match_account);
+    instruction.accounts[3] = AccountMeta::new_readonly(crate::ID, false);
+
+    let result = env.send(&[instruction]);
+
+    assert_eq!(result, Err(program_error(PV3Error::AbandonerProfileRequired)));
+}
+
+#[test]
+fn released_flag_pays_an_abandonment_under_its_fee_policy() {
+    let (mut env, platform) = setup();
+    waive_abandonment_fee(&mut env, &platform);
+    enable_challenge_period(&mut env, &platform);
+    let (match_account, creator, joiner) = started_match(&mut env);
+    let balance_before = env.lamports(&creator);
+    env.send(&submit_abandonment(&platform, &match_account, &joiner, &creator)).unwrap();
+    env.send(&[flag_match_ix(&match_account, &joiner)]).unwrap();
+
+    env.send(&[resolve_flagged_match_ix(&env, &platform, &match_account, true)]).unwrap();
+
+    let abandoned = &env.events::<MatchAbandoned>()[0];
+    assert_eq!((abandoned.platform_fee, abandoned.abandonments), (0, 1));
+    assert_eq!(env.lamports(&creator), balance_before + 2 * WAGER);
+}
+
+#[test]
+fn voided_abandonment_is_not_counted() {
+    let (mut env, platform) = setup();
+    enable_challenge_period(&mut env, &platform);
+    let (match_account, creator, joiner) = started_match(&mut env);
+    env.send(&submit_abandonment(&platform, &match_account, &joiner, &creator)).unwrap();
+    env.send(&[flag_match_ix(&match_account, &joiner)]).unwrap();
+
+    env.send(&[resolve_flagged_match_ix(&env, &platform, &match_account, false)]).unwrap();
+
+    assert!(match_state(&env, &match_account).status == MatchStatus::Refunded);
+    assert_eq!(env.get::<PlayerProfile>(&profile_pda(&joiner)).abandonments, 0);
+}
+
+#[test]
+fn upheld_abandonment_dispute_pays_under_its_fee_policy() {
+    let (mut env, platform) = setup();
+    waive_abandonment_fee(&mut env, &platform);
+    enable_challenge_period(&mut env, &platform);
+    let (match_account, creator, joiner) = started_match(&mut env);
+    let balance_before = env.lamports(&creator);
+    env.send(&submit_abandonment(&platform, &match_account, &joiner, &creator)).unwrap();
+    env.send(&[open_dispute_ix(&match_account, &joiner)]).unwrap();
+
+    let resolve = resolve_dispute_ix(&env, &platform, &match_account, DisputeResolution::UpholdResult);
+    env.send(&[resolve]).unwrap();
+
+    let abandoned = &env.events::<MatchAbandoned>()[0];
+    assert_eq!((abandoned.platform_fee, abandoned.abandonments), (0, 1));
+    assert_eq!(env.lamports(&creator), balance_before + 2 * WAGER);
+}
+
+#[test]
+fn reversed_abandonment_dispute_pays_a_normal_win() {
+    let (mut env, platform) = setup();
+    waive_abandonment_fee(&mut env, &platform);
+    enable_challenge_period(&mut env, &platform);
+    let (match_account, creator, joiner) = started_match(&mut env);
+    env.send(&submit_abandonment(&platform, &match_account, &joiner, &creator)).unwrap();
+    env.send(&[open_dispute_ix(&match_account, &joiner)]).unwrap();
+
+    let resolve = resolve_dispute_ix(&env, &platform, &match_account, DisputeResolution::ReverseResult);
+    env.send(&[resolve]).unwrap();
+
+    let completed = &env.events::<MatchCompleted>()[0];
+    assert_eq!(completed.winner, joiner);
+    assert!(completed.platform_fee > 0);
+    assert_eq!(env.get::<PlayerProfile>(&profile_pda(&joiner)).abandonments, 0);
+}
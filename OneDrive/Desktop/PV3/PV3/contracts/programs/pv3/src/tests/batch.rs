@@ -0,0 +1,189 @@
+use super::*;
+use crate::batch::{BatchEntryProcessed, BatchOutcome, BatchResult};
+use crate::{MatchCompleted, MatchStatus};
+
+const RESULT_HASH: [u8; 32] = [8; 32];
+
+fn error_code(error: PV3Error) -> u64 {
+    u32::from(error) as u64
+}
+
+fn refund_entry(env: &TestEnv, match_account: &Pubkey) -> [Pubkey; 4] {
+    let state = match_state(env, match_account);
+    [*match_account, escrow_pda(match_account), state.creator, state.joiner]
+}
+
+fn batch_refund_ix(keeper: &Pubkey, entries: &[[Pubkey; 4]]) -> Instruction {
+    let instruction = ix(
+        crate::accounts::BatchRefundMatches {
+            config: config_pda(),
+            keeper: *keeper,
+        },
+        crate::instruction::BatchRefundMatches {},
+    );
+    with_targets(instruction, &entries.concat())
+}
+
+fn batch_settle(
+    platform: &Platform,
+    verdicts: &[(Pubkey, Pubkey)],
+) -> Vec<Instruction> {
+    let mut ed25519_instructions = Vec::new();
+    let mut results = Vec::new();
+    let mut entries = Vec::new();
+    for (position, (match_account, winner)) in verdicts.iter().enumerate() {
+        let message = crate::create_result_message(*match_account, *winner, RESULT_HASH);
+        let (signature, ed25519) = signed_by_verifier(platform, &message);
+        ed25519_instructions.push(ed25519);
+        results.push(BatchResult {
+            winner: *winner,
+            result_hash: RESULT_HASH,
+            ed25519_signature: signature,
+            ed25519_instruction_index: position as u8 + 1,
+        });
+        entries.extend([*match_account, escrow_pda(match_account), *winner]);
+    }
+    let submit = ix(
+        crate::accounts::BatchSubmitResults {
+            treasury: platform.treasury,
+            referral_pool: platform.referral_pool,
+            config: config_pda(),
+            instruction_sysvar: anchor_lang::solana_program::sysvar::instructions::ID,
+        },
+        crate::instruction::BatchSubmitResults { results },
+    );
+    let mut instructions = vec![with_targets(submit, &entries)];
+    instructions.extend(ed25519_instructions);
+    instructions
+}
+
+fn outcomes(env: &TestEnv) -> Vec<(BatchOutcome, u64)> {
+    env.events::<BatchEntryProcessed>()
+        .iter()
+        .map(|event| (event.outcome, event.error_code))
+        .collect()
+}
+
+#[test]
+fn expired_matches_are_refunded_in_one_batch() {
+    let (mut env, _) = setup();
+    let keeper = env.wallet(SOL);
+    let (first, creator, joiner) = started_match(&mut env);
+    let (second, _, _) = started_match(&mut env);
+    let entries = [refund_entry(&env, &first), refund_entry(&env, &second)];
+    let creator_before = env.lamports(&creator);
+    let joiner_before = env.lamports(&joiner);
+
+    env.warp(RESULT_WINDOW + 1);
+    env.send(&[batch_refund_ix(&keeper, &entries)]).unwrap();
+
+    assert!(outcomes(&env) == vec![(BatchOutcome::Refunded, 0), (BatchOutcome::Refunded, 0)]);
+    assert_eq!(env.lamports(&creator), creator_before + WAGER);
+    assert_eq!(env.lamports(&joiner), joiner_before + WAGER);
+    assert!(match_state(&env, &second).status == MatchStatus::Refunded);
+}
+
+#[test]
+fn unexpired_entry_is_skipped_without_reverting_the_batch() {
+    let (mut env, _) = setup();
+    let keeper = env.wallet(SOL);
+    let (expired, _, _) = started_match(&mut env);
+    env.warp(RESULT_WINDOW + 1);
+    let (fresh, _, _) = started_match(&mut env);
+    let entries = [refund_entry(&env, &fresh), refund_entry(&env, &expired)];
+
+    env.send(&[batch_refund_ix(&keeper, &entries)]).unwrap();
+
+    let skipped = (BatchOutcome::Skipped, error_code(PV3Error::ResultDeadlineNotReached));
+    assert!(outcomes(&env) == vec![skipped, (BatchOutcome::Refunded, 0)]);
+    assert!(match_state(&env, &fresh).status == MatchStatus::InProgress);
+    assert!(match_state(&env, &expired).status == MatchStatus::Refunded);
+}
+
+#[test]
+fn read_only_refund_entry_is_skipped() {
+    let (mut env, _) = setup();
+    let keeper = env.wallet(SOL);
+    let (read_only, creator, _) = started_match(&mut env);
+    let (writable, _, _) = started_match(&mut env);
+    let entries = [refund_entry(&env, &read_only), refund_entry(&env, &writable)];
+    let mut instruction = batch_refund_ix(&keeper, &entries);
+    // The read-only entry's refund target, which the runtime would refuse to credit
+    let creator_meta = instruction.accounts.iter_mut().find(|meta| meta.pubkey == creator).unwrap();
+    creator_meta.is_writable = false;
+    let creator_before = env.lamports(&creator);
+
+    env.warp(RESULT_WINDOW + 1);
+    env.send(&[instruction]).unwrap();
+
+    let skipped = (BatchOutcome::Skipped, error_code(PV3Error::BatchEntryNotWritable));
+    assert!(outcomes(&env) == vec![skipped, (BatchOutcome::Refunded, 0)]);
+    assert_eq!(env.lamports(&creator), creator_before);
+    assert!(match_state(&env, &read_only).status == MatchStatus::InProgress);
+    assert!(match_state(&env, &writable).status == MatchStatus::Refunded);
+}
+
+#[test]
+fn refund_batch_must_hold_whole_entries() {
+    let (mut env, _) = setup();
+    let keeper = env.wallet(SOL);
+    let (match_account, _, _) = started_match(&mut env);
+    let mut instruction = batch_refund_ix(&keeper, &[refund_entry(&env, &match_account)]);
+    instruction.accounts.pop();
+
+    let result = env.send(&[instruction]);
+
+    assert_eq!(result, Err(program_error(PV3Error::InvalidBatchAccounts)));
+}
+
+#[test]
+fn verified_results_are_settled_in_one_batch() {
+    let (mut env, platform) = setup();
+    let (first, first_winner, _) = started_match(&mut env);
+    let (second, _, second_winner) = started_match(&mut env);
+    let balance_before = env.lamports(&second_winner);
+
+    env.send(&batch_settle(&platform, &[(first, first_winner), (second, second_winner)])).unwrap();
+
+    assert!(outcomes(&env) == vec![(BatchOutcome::Settled, 0), (BatchOutcome::Settled, 0)]);
+    let completed = &env.events::<MatchCompleted>()[1];
+    assert_eq!(env.lamports(&second_winner), balance_before + completed.winner_amount);
+    assert!(match_state(&env, &first).status == MatchStatus::Completed);
+}
+
+#[test]
+fn read_only_settlement_entry_is_skipped() {
+    let (mut env, platform) = setup();
+    let (read_only, read_only_winner, _) = started_match(&mut env);
+    let (writable, writable_winner, _) = started_match(&mut env);
+    let mut instructions = batch_settle(
+        &platform,
+        &[(read_only, read_only_winner), (writable, writable_winner)],
+    );
+    let match_meta = instructions[0].accounts.iter_mut().find(|meta| meta.pubkey == read_only).unwrap();
+    match_meta.is_writable = false;
+
+    env.send(&instructions).unwrap();
+
+    let skipped = (BatchOutcome::Skipped, error_code(PV3Error::BatchEntryNotWritable));
+    assert!(outcomes(&env) == vec![skipped, (BatchOutcome::Settled, 0)]);
+    assert!(match_state(&env, &read_only).status == MatchStatus::InProgress);
+    assert!(match_state(&env, &writable).status == MatchStatus::Completed);
+}
+
+#[test]
+fn settlement_entry_with_a_bad_signature_is_skipped() {
+    let (mut env, platform) = setup();
+    let (forged, _, joiner) = started_match(&mut env);
+    let (honest, honest_winner, _) = started_match(&mut env);
+    let mut instructions = batch_settle(&platform, &[(forged, joiner), (honest, honest_winner)]);
+    // The verifier never signed a verdict for the joiner, swap in one it did sign
+    let honest_message = crate::create_result_message(honest, honest_winner, RESULT_HASH);
+    instructions[1] = signed_by_verifier(&platform, &honest_message).1;
+
+    env.send(&instructions).unwrap();
+
+    let skipped = (BatchOutcome::Skipped, error_code(PV3Error::SignatureMismatch));
+    assert!(outcomes(&env) == vec![skipped, (BatchOutcome::Settled, 0)]);
+    assert!(match_state(&env, &forged).status == MatchStatus::InProgress);
+}
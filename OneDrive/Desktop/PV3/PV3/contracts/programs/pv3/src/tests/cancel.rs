@@ -0,0 +1,56 @@
+use super::*;
+use crate::{MatchCancelled, MatchStatus};
+
+fn cancel_match_ix(match_account: &Pubkey, creator: &Pubkey, session_vault: Option<Pubkey>) -> Instruction {
+    ix(
+        crate::accounts::CancelMatch {
+            match_account: *match_account,
+            match_escrow: escrow_pda(match_account),
+            creator: *creator,
+            creator_session_vault: session_vault,
+        },
+        crate::instruction::CancelMatch {},
+    )
+}
+
+#[test]
+fn creator_cancels_unjoined_match_and_is_refunded() {
+    let (mut env, _) = setup();
+    let creator = player(&mut env);
+    let match_account = create_match(&mut env, &creator, &MatchOptions::default());
+    let balance_before = env.lamports(&creator);
+
+    env.send(&[cancel_match_ix(&match_account, &creator, None)]).unwrap();
+
+    assert_eq!(env.lamports(&creator), balance_before + WAGER);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Cancelled);
+    let events = env.events::<MatchCancelled>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].refund_amount, WAGER);
+}
+
+#[test]
+fn only_the_creator_can_cancel() {
+    let (mut env, _) = setup();
+    let creator = player(&mut env);
+    let stranger = player(&mut env);
+    let match_account = create_match(&mut env, &creator, &MatchOptions::default());
+
+    let result = env.send(&[cancel_match_ix(&match_account, &stranger, None)]);
+
+    assert_eq!(result, Err(program_error(PV3Error::NotMatchCreator)));
+}
+
+#[test]
+fn joined_or_cancelled_matches_cannot_be_cancelled() {
+    let (mut env, _) = setup();
+    let (match_account, creator, _) = started_match(&mut env);
+
+    let result = env.send(&[cancel_match_ix(&match_account, &creator, None)]);
+    assert_eq!(result, Err(program_error(PV3Error::CancelNotAllowed)));
+
+    let open_match = create_match(&mut env, &creator, &MatchOptions::default());
+    env.send(&[cancel_match_ix(&open_match, &creator, None)]).unwrap();
+    let result = env.send(&[cancel_match_ix(&open_match, &creator, None)]);
+    assert_eq!(result, Err(program_error(PV3Error::MatchAlreadyCancelled)));
+}
@@ -0,0 +1,148 @@
+use super::*;
+use crate::{MatchCompleted, MatchDrawn, MatchResultPending, MatchStatus};
+
+const RESULT_HASH: [u8; 32] = [6; 32];
+
+#[test]
+fn verified_win_waits_out_the_challenge_period() {
+    let (mut env, platform) = setup();
+    enable_challenge_period(&mut env, &platform);
+    let (match_account, creator, _) = started_match(&mut env);
+    let balance_before = env.lamports(&creator);
+
+    env.send(&verified_result(&platform, &match_account, &creator, RESULT_HASH)).unwrap();
+    assert!(match_state(&env, &match_account).status == MatchStatus::PendingSettlement);
+    assert_eq!(env.lamports(&creator), balance_before);
+
+    let result = env.send(&[finalize_match_ix(&env, &platform, &match_account)]);
+    assert_eq!(result, Err(program_error(PV3Error::ChallengePeriodActive)));
+
+    env.warp(CHALLENGE_PERIOD);
+    env.send(&[finalize_match_ix(&env, &platform, &match_account)]).unwrap();
+
+    let completed = &env.events::<MatchCompleted>()[0];
+    assert_eq!(env.lamports(&creator), balance_before + completed.winner_amount);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Completed);
+}
+
+#[test]
+fn flagged_win_is_held_until_admins_resolve_it() {
+    let (mut env, platform) = setup();
+    enable_challenge_period(&mut env, &platform);
+    let (match_account, creator, joiner) = started_match(&mut env);
+    env.send(&verified_result(&platform, &match_account, &creator, RESULT_HASH)).unwrap();
+
+    // The winner has nothing to contest
+    let result = env.send(&[flag_match_ix(&match_account, &creator)]);
+    assert_eq!(result, Err(program_error(PV3Error::UnauthorizedFlag)));
+
+    env.send(&[flag_match_ix(&match_account, &joiner)]).unwrap();
+    env.warp(CHALLENGE_PERIOD);
+    let result = env.send(&[finalize_match_ix(&env, &platform, &match_account)]);
+    assert_eq!(result, Err(program_error(PV3Error::MatchFlagged)));
+
+    let creator_before = env.lamports(&creator);
+    let joiner_before = env.lamports(&joiner);
+    env.send(&[resolve_flagged_match_ix(&env, &platform, &match_account, false)]).unwrap();
+
+    assert_eq!(env.lamports(&creator), creator_before + WAGER);
+    assert_eq!(env.lamports(&joiner), joiner_before + WAGER);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Refunded);
+}
+
+#[test]
+fn verified_draw_waits_out_the_challenge_period() {
+    let (mut env, platform) = setup();
+    enable_challenge_period(&mut env, &platform);
+    let (match_account, creator, joiner) = started_match(&mut env);
+    let creator_before = env.lamports(&creator);
+
+    env.send(&verified_draw(&env, &platform, &match_account, RESULT_HASH)).unwrap();
+
+    let pending = &env.events::<MatchResultPending>()[0];
+    assert_eq!(pending.winner, Pubkey::default());
+    assert_eq!(pending.settle_after, env.now + CHALLENGE_PERIOD);
+    assert!(match_state(&env, &match_account).status == MatchStatus::PendingSettlement);
+    assert_eq!(env.lamports(&creator), creator_before);
+
+    let result = env.send(&[finalize_draw_ix(&env, &platform, &match_account)]);
+    assert_eq!(result, Err(program_error(PV3Error::ChallengePeriodActive)));
+
+    env.warp(CHALLENGE_PERIOD);
+    let joiner_before = env.lamports(&joiner);
+    env.send(&[finalize_draw_ix(&env, &platform, &match_account)]).unwrap();
+
+    let drawn = &env.events::<MatchDrawn>()[0];
+    assert_eq!(env.lamports(&creator), creator_before + drawn.creator_amount);
+    assert_eq!(env.lamports(&joiner), joiner_before + drawn.joiner_amount);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Drawn);
+}
+
+#[test]
+fn pending_wins_and_draws_need_their_own_finalize() {
+    let (mut env, platform) = setup();
+    enable_challenge_period(&mut env, &platform);
+    let (drawn_match, creator, joiner) = started_match(&mut env);
+    let won_match = create_match(&mut env, &creator, &MatchOptions::default());
+    env.send(&[join_match_ix(&won_match, &joiner, false, None)]).unwrap();
+    env.send(&verified_draw(&env, &platform, &drawn_match, RESULT_HASH)).unwrap();
+    env.send(&verified_result(&platform, &won_match, &creator, RESULT_HASH)).unwrap();
+    env.warp(CHALLENGE_PERIOD);
+
+    let result = env.send(&[finalize_match_ix(&env, &platform, &drawn_match)]);
+    assert_eq!(result, Err(program_error(PV3Error::PendingDraw)));
+
+    let result = env.send(&[finalize_draw_ix(&env, &platform, &won_match)]);
+    assert_eq!(result, Err(program_error(PV3Error::NotAPendingDraw)));
+}
+
+#[test]
+fn either_player_may_flag_a_draw_and_admins_can_release_it() {
+    let (mut env, platform) = setup();
+    enable_challenge_period(&mut env, &platform);
+    let (match_account, creator, joiner) = started_match(&mut env);
+    let stranger = player(&mut env);
+    env.send(&verified_draw(&env, &platform, &match_account, RESULT_HASH)).unwrap();
+
+    let result = env.send(&[flag_match_ix(&match_account, &stranger)]);
+    assert_eq!(result, Err(program_error(PV3Error::UnauthorizedFlag)));
+
+    env.send(&[flag_match_ix(&match_account, &creator)]).unwrap();
+    let result = env.send(&[finalize_draw_ix(&env, &platform, &match_account)]);
+    assert_eq!(result, Err(program_error(PV3Error::MatchFlagged)));
+
+    let joiner_before = env.lamports(&joiner);
+    env.send(&[resolve_flagged_match_ix(&env, &platform, &match_account, true)]).unwrap();
+
+    let drawn = &env.events::<MatchDrawn>()[0];
+    assert_eq!(env.lamports(&joiner), joiner_before + drawn.joiner_amount);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Drawn);
+}
+
+#[test]
+fn joiner_may_flag_a_draw_too() {
+    let (mut env, platform) = setup();
+    enable_challenge_period(&mut env, &platform);
+    let (match_account, _, joiner) = started_match(&mut env);
+    env.send(&verified_draw(&env, &platform, &match_account, RESULT_HASH)).unwrap();
+
+    env.send(&[flag_match_ix(&match_account, &joiner)]).unwrap();
+
+    assert!(match_state(&env, &match_account).status == MatchStatus::Flagged);
+}
+
+#[test]
+fn challenge_period_is_capped_at_seven_days() {
+    let (mut env, platform) = setup();
+
+    for challenge_period in [-1, 7 * 24 * 3600 + 1] {
+        let result = env.send(&[ix(
+            crate::accounts::EmergencyAction {
+                config: config_pda(),
+                signer: platform.admins[0],
+            },
+            crate::instruction::UpdateChallengePeriod { challenge_period },
+        )]);
+        assert_eq!(result, Err(program_error(PV3Error::InvalidChallengePeriod)));
+    }
+}
@@ -0,0 +1,77 @@
+use super::*;
+use crate::{DisputeResolution, MatchClosed};
+
+#[test]
+fn settled_match_returns_its_rent_to_the_creator() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, _) = started_match(&mut env);
+    env.send(&verified_result(&platform, &match_account, &creator, [1; 32])).unwrap();
+    let rent = env.lamports(&match_account);
+    let escrow_rent = env.lamports(&escrow_pda(&match_account));
+    let balance_before = env.lamports(&creator);
+
+    env.send(&[close_match_ix(&env, &match_account)]).unwrap();
+
+    let closed = &env.events::<MatchClosed>()[0];
+    assert_eq!((closed.rent_reclaimed, closed.escrow_swept), (rent, escrow_rent));
+    assert_eq!(env.lamports(&creator), balance_before + rent + escrow_rent);
+    assert!(!env.exists(&escrow_pda(&match_account)));
+}
+
+#[test]
+fn match_with_funds_at_stake_cannot_be_closed() {
+    let (mut env, _) = setup();
+    let (match_account, _, _) = started_match(&mut env);
+
+    let result = env.send(&[close_match_ix(&env, &match_account)]);
+
+    assert_eq!(result, Err(program_error(PV3Error::MatchNotSettled)));
+}
+
+#[test]
+fn only_the_creator_can_close_a_match() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, joiner) = started_match(&mut env);
+    env.send(&verified_result(&platform, &match_account, &creator, [1; 32])).unwrap();
+    let mut instruction = close_match_ix(&env, &match_account);
+    instruction.accounts.last_mut().unwrap().pubkey = joiner;
+
+    let result = env.send(&[instruction]);
+
+    assert_eq!(result, Err(program_error(PV3Error::NotMatchCreator)));
+}
+
+#[test]
+fn dispute_rent_goes_back_to_the_player_who_opened_it() {
+    let (mut env, platform) = setup();
+    let (match_account, _, joiner) = started_match(&mut env);
+    env.send(&[open_dispute_ix(&match_account, &joiner)]).unwrap();
+    let resolve = resolve_dispute_ix(&env, &platform, &match_account, DisputeResolution::Refund);
+    env.send(&[resolve]).unwrap();
+    let dispute_rent = env.lamports(&dispute_pda(&match_account));
+    let joiner_before = env.lamports(&joiner);
+
+    env.send(&[close_match_ix(&env, &match_account)]).unwrap();
+
+    assert!(!env.exists(&dispute_pda(&match_account)));
+    assert_eq!(env.lamports(&joiner), joiner_before + dispute_rent);
+}
+
+#[test]
+fn disputed_match_needs_the_dispute_opener_to_close() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, joiner) = started_match(&mut env);
+    env.send(&[open_dispute_ix(&match_account, &joiner)]).unwrap();
+    let resolve = resolve_dispute_ix(&env, &platform, &match_account, DisputeResolution::Refund);
+    env.send(&[resolve]).unwrap();
+
+    let mut missing_opener = close_match_ix(&env, &match_account);
+    missing_opener.accounts[4] = AccountMeta::new_readonly(crate::ID, false);
+    let result = env.send(&[missing_opener]);
+    assert_eq!(result, Err(program_error(PV3Error::DisputeOpenerMismatch)));
+
+    let mut wrong_opener = close_match_ix(&env, &match_account);
+    wrong_opener.accounts[4].pubkey = creator;
+    let result = env.send(&[wrong_opener]);
+    assert_eq!(result, Err(program_error(PV3Error::DisputeOpenerMismatch)));
+}
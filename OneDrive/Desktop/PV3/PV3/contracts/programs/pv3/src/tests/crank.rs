@@ -0,0 +1,117 @@
+use super::*;
+use crate::{ExpiredMatchCranked, PlatformConfig, CRANK_BOUNTY_DELAY};
+
+const BOUNTY: u64 = SOL / 1000;
+
+fn update_crank_bounty_ix(admin: &Pubkey, crank_bounty: u64) -> Instruction {
+    ix(
+        crate::accounts::EmergencyAction {
+            config: config_pda(),
+            signer: *admin,
+        },
+        crate::instruction::UpdateCrankBounty { crank_bounty },
+    )
+}
+
+fn fund_crank_budget_ix(admin: &Pubkey, amount: u64) -> Instruction {
+    ix(
+        crate::accounts::FundCrankBudget {
+            config: config_pda(),
+            signer: *admin,
+            system_program: system_program::ID,
+        },
+        crate::instruction::FundCrankBudget { amount },
+    )
+}
+
+// Sets a bounty and funds the budget for `cranks` payouts
+fn fund_bounty(env: &mut TestEnv, platform: &Platform, cranks: u64) {
+    env.send(&[update_crank_bounty_ix(&platform.admins[0], BOUNTY)]).unwrap();
+    env.send(&[fund_crank_budget_ix(&platform.admins[0], cranks * BOUNTY)]).unwrap();
+}
+
+// An unjoined match that has sat past its join deadline long enough to earn the bounty
+fn expired_match(env: &mut TestEnv) -> (Pubkey, Pubkey) {
+    let creator = player(env);
+    let match_account = create_match(env, &creator, &MatchOptions::default());
+    env.warp(JOIN_WINDOW + CRANK_BOUNTY_DELAY);
+    (match_account, creator)
+}
+
+#[test]
+fn keeper_is_paid_the_bounty_from_the_budget() {
+    let (mut env, platform) = setup();
+    env.send(&[update_crank_bounty_ix(&platform.admins[0], BOUNTY)]).unwrap();
+    let config_before = env.lamports(&config_pda());
+    env.send(&[fund_crank_budget_ix(&platform.admins[0], BOUNTY)]).unwrap();
+    assert_eq!(env.lamports(&config_pda()), config_before + BOUNTY);
+
+    let (match_account, _) = expired_match(&mut env);
+    let keeper = env.wallet(SOL);
+    env.send(&[refund_match_ix(&env, &match_account, &keeper)]).unwrap();
+
+    let cranked = &env.events::<ExpiredMatchCranked>()[0];
+    assert_eq!((cranked.keeper_bounty, cranked.remaining_budget), (BOUNTY, 0));
+    assert_eq!(env.lamports(&keeper), SOL + BOUNTY);
+    assert_eq!(env.lamports(&config_pda()), config_before);
+}
+
+#[test]
+fn refund_still_goes_through_once_the_budget_runs_out() {
+    let (mut env, platform) = setup();
+    env.send(&[update_crank_bounty_ix(&platform.admins[0], BOUNTY)]).unwrap();
+    env.send(&[fund_crank_budget_ix(&platform.admins[0], BOUNTY / 2)]).unwrap();
+
+    let (match_account, _) = expired_match(&mut env);
+    let keeper = env.wallet(SOL);
+    env.send(&[refund_match_ix(&env, &match_account, &keeper)]).unwrap();
+
+    assert_eq!(env.events::<ExpiredMatchCranked>()[0].keeper_bounty, 0);
+    assert_eq!(env.lamports(&keeper), SOL);
+    assert_eq!(env.get::<PlatformConfig>(&config_pda()).crank_budget, BOUNTY / 2);
+}
+
+#[test]
+fn players_cranking_their_own_match_earn_no_bounty() {
+    let (mut env, platform) = setup();
+    fund_bounty(&mut env, &platform, 1);
+    let (match_account, creator) = expired_match(&mut env);
+    let balance_before = env.lamports(&creator);
+
+    env.send(&[refund_match_ix(&env, &match_account, &creator)]).unwrap();
+
+    assert_eq!(env.events::<ExpiredMatchCranked>()[0].keeper_bounty, 0);
+    assert_eq!(env.lamports(&creator), balance_before + WAGER);
+    assert_eq!(env.get::<PlatformConfig>(&config_pda()).crank_budget, BOUNTY);
+}
+
+#[test]
+fn keepers_earn_the_bounty_only_after_the_delay() {
+    let (mut env, platform) = setup();
+    fund_bounty(&mut env, &platform, 1);
+    let creator = player(&mut env);
+    let match_account = create_match(&mut env, &creator, &MatchOptions::default());
+    env.warp(JOIN_WINDOW + 1);
+    let keeper = env.wallet(SOL);
+
+    env.send(&[refund_match_ix(&env, &match_account, &keeper)]).unwrap();
+
+    assert_eq!(env.events::<ExpiredMatchCranked>()[0].keeper_bounty, 0);
+    assert_eq!(env.lamports(&keeper), SOL);
+    assert!(match_state(&env, &match_account).status == crate::MatchStatus::Refunded);
+}
+
+#[test]
+fn crank_budget_is_admin_funded_and_non_zero() {
+    let (mut env, platform) = setup();
+    let outsider = env.wallet(SOL);
+
+    let result = env.send(&[fund_crank_budget_ix(&outsider, BOUNTY)]);
+    assert_eq!(result, Err(program_error(PV3Error::UnauthorizedAdmin)));
+
+    let result = env.send(&[update_crank_bounty_ix(&outsider, BOUNTY)]);
+    assert_eq!(result, Err(program_error(PV3Error::UnauthorizedAdmin)));
+
+    let result = env.send(&[fund_crank_budget_ix(&platform.admins[0], 0)]);
+    assert_eq!(result, Err(program_error(PV3Error::InvalidAmount)));
+}
@@ -0,0 +1,75 @@
+use super::*;
+use crate::{MatchRefunded, MatchStatus, MAX_RESULT_WINDOW};
+
+#[test]
+fn result_window_must_be_positive_and_at_most_seven_days() {
+    let (mut env, _) = setup();
+    let creator = player(&mut env);
+
+    for result_window in [0, -1, MAX_RESULT_WINDOW + 1, i64::MAX] {
+        let options = MatchOptions { result_window, ..MatchOptions::default() };
+        let result = env.send(&[create_match_ix(&env, &creator, &options)]);
+        assert_eq!(result, Err(program_error(PV3Error::InvalidResultWindow)));
+    }
+
+    let options = MatchOptions { result_window: MAX_RESULT_WINDOW, ..MatchOptions::default() };
+    create_match(&mut env, &creator, &options);
+}
+
+#[test]
+fn unjoined_match_refunds_only_after_the_join_deadline() {
+    let (mut env, _) = setup();
+    let creator = player(&mut env);
+    let keeper = env.wallet(SOL);
+    let match_account = create_match(&mut env, &creator, &MatchOptions::default());
+
+    let result = env.send(&[refund_match_ix(&env, &match_account, &keeper)]);
+    assert_eq!(result, Err(program_error(PV3Error::JoinDeadlineNotReached)));
+
+    env.warp(JOIN_WINDOW + 1);
+    let balance_before = env.lamports(&creator);
+    env.send(&[refund_match_ix(&env, &match_account, &keeper)]).unwrap();
+
+    assert_eq!(env.lamports(&creator), balance_before + WAGER);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Refunded);
+    assert_eq!(env.events::<MatchRefunded>()[0].refund_amount, WAGER);
+}
+
+#[test]
+fn started_match_refunds_both_players_only_after_the_result_deadline() {
+    let (mut env, _) = setup();
+    let (match_account, creator, joiner) = started_match(&mut env);
+    let keeper = env.wallet(SOL);
+
+    // The join deadline has passed, but the game is under way until the result deadline
+    env.warp(RESULT_WINDOW);
+    let result = env.send(&[refund_match_ix(&env, &match_account, &keeper)]);
+    assert_eq!(result, Err(program_error(PV3Error::ResultDeadlineNotReached)));
+
+    env.warp(1);
+    let creator_before = env.lamports(&creator);
+    let joiner_before = env.lamports(&joiner);
+    env.send(&[refund_match_ix(&env, &match_account, &keeper)]).unwrap();
+
+    assert_eq!(env.lamports(&creator), creator_before + WAGER);
+    assert_eq!(env.lamports(&joiner), joiner_before + WAGER);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Refunded);
+}
+
+#[test]
+fn settled_or_refunded_matches_cannot_be_refunded() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, _) = started_match(&mut env);
+    let keeper = env.wallet(SOL);
+    env.send(&verified_result(&platform, &match_account, &creator, [1; 32])).unwrap();
+
+    env.warp(JOIN_WINDOW + RESULT_WINDOW + 1);
+    let result = env.send(&[refund_match_ix(&env, &match_account, &keeper)]);
+    assert_eq!(result, Err(program_error(PV3Error::MatchAlreadySettled)));
+
+    let expired = create_match(&mut env, &creator, &MatchOptions::default());
+    env.warp(JOIN_WINDOW + 1);
+    env.send(&[refund_match_ix(&env, &expired, &keeper)]).unwrap();
+    let result = env.send(&[refund_match_ix(&env, &expired, &keeper)]);
+    assert_eq!(result, Err(program_error(PV3Error::MatchAlreadyRefunded)));
+}
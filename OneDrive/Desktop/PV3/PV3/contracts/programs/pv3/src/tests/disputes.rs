@@ -0,0 +1,160 @@
+use super::*;
+use crate::{Dispute, DisputeResolution, DisputeResolved, MatchCompleted, MatchDrawn, MatchStatus};
+
+const RESULT_HASH: [u8; 32] = [4; 32];
+
+// A match whose creator win is recorded but still inside the challenge period
+fn pending_verdict(env: &mut TestEnv, platform: &Platform) -> (Pubkey, Pubkey, Pubkey) {
+    enable_challenge_period(env, platform);
+    let (match_account, creator, joiner) = started_match(env);
+    env.send(&verified_result(platform, &match_account, &creator, RESULT_HASH)).unwrap();
+    (match_account, creator, joiner)
+}
+
+#[test]
+fn dispute_freezes_a_pending_verdict() {
+    let (mut env, platform) = setup();
+    let (match_account, _, joiner) = pending_verdict(&mut env, &platform);
+
+    env.send(&[open_dispute_ix(&match_account, &joiner)]).unwrap();
+
+    let dispute = env.get::<Dispute>(&dispute_pda(&match_account));
+    assert_eq!(dispute.opened_by, joiner);
+    assert!(dispute.resolution.is_none());
+    assert!(match_state(&env, &match_account).status == MatchStatus::Disputed);
+
+    env.warp(CHALLENGE_PERIOD);
+    let result = env.send(&[finalize_match_ix(&env, &platform, &match_account)]);
+    assert_eq!(result, Err(program_error(PV3Error::MatchNotPendingSettlement)));
+}
+
+#[test]
+fn upheld_verdict_pays_the_recorded_winner() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, joiner) = pending_verdict(&mut env, &platform);
+    env.send(&[open_dispute_ix(&match_account, &joiner)]).unwrap();
+    let balance_before = env.lamports(&creator);
+
+    let resolve = resolve_dispute_ix(&env, &platform, &match_account, DisputeResolution::UpholdResult);
+    env.send(&[resolve]).unwrap();
+
+    let completed = &env.events::<MatchCompleted>()[0];
+    assert_eq!(completed.winner, creator);
+    assert_eq!(env.lamports(&creator), balance_before + completed.winner_amount);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Completed);
+}
+
+#[test]
+fn reversed_verdict_pays_the_other_player() {
+    let (mut env, platform) = setup();
+    let (match_account, _, joiner) = pending_verdict(&mut env, &platform);
+    env.send(&[open_dispute_ix(&match_account, &joiner)]).unwrap();
+    let balance_before = env.lamports(&joiner);
+
+    let resolve = resolve_dispute_ix(&env, &platform, &match_account, DisputeResolution::ReverseResult);
+    env.send(&[resolve]).unwrap();
+
+    let completed = &env.events::<MatchCompleted>()[0];
+    assert_eq!(completed.winner, joiner);
+    assert_eq!(env.lamports(&joiner), balance_before + completed.winner_amount);
+    assert_eq!(env.events::<DisputeResolved>()[0].winner, joiner);
+    assert_eq!(match_state(&env, &match_account).winner, joiner);
+}
+
+#[test]
+fn refund_returns_both_wagers() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, joiner) = started_match(&mut env);
+    env.send(&[open_dispute_ix(&match_account, &creator)]).unwrap();
+    let creator_before = env.lamports(&creator);
+    let joiner_before = env.lamports(&joiner);
+
+    let resolve = resolve_dispute_ix(&env, &platform, &match_account, DisputeResolution::Refund);
+    env.send(&[resolve]).unwrap();
+
+    assert_eq!(env.lamports(&creator), creator_before + WAGER);
+    assert_eq!(env.lamports(&joiner), joiner_before + WAGER);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Refunded);
+    let dispute = env.get::<Dispute>(&dispute_pda(&match_account));
+    assert!(dispute.resolution == Some(DisputeResolution::Refund));
+}
+
+#[test]
+fn mid_game_dispute_has_no_verdict_to_uphold_or_reverse() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, _) = started_match(&mut env);
+    env.send(&[open_dispute_ix(&match_account, &creator)]).unwrap();
+
+    for resolution in [DisputeResolution::UpholdResult, DisputeResolution::ReverseResult] {
+        let resolve = resolve_dispute_ix(&env, &platform, &match_account, resolution);
+        let result = env.send(&[resolve]);
+        assert_eq!(result, Err(program_error(PV3Error::NoVerdictToArbitrate)));
+    }
+}
+
+#[test]
+fn only_players_can_dispute_a_match_with_funds_at_stake() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, joiner) = started_match(&mut env);
+    let stranger = player(&mut env);
+
+    let result = env.send(&[open_dispute_ix(&match_account, &stranger)]);
+    assert_eq!(result, Err(program_error(PV3Error::InvalidParticipant)));
+
+    env.send(&verified_result(&platform, &match_account, &creator, RESULT_HASH)).unwrap();
+    let result = env.send(&[open_dispute_ix(&match_account, &joiner)]);
+    assert_eq!(result, Err(program_error(PV3Error::DisputeNotAllowed)));
+}
+
+#[test]
+fn pending_verdict_cannot_be_disputed_after_the_challenge_period() {
+    let (mut env, platform) = setup();
+    let (match_account, _, joiner) = pending_verdict(&mut env, &platform);
+
+    env.warp(CHALLENGE_PERIOD);
+    let result = env.send(&[open_dispute_ix(&match_account, &joiner)]);
+
+    assert_eq!(result, Err(program_error(PV3Error::ChallengePeriodEnded)));
+}
+
+#[test]
+fn resolution_needs_two_different_admins() {
+    let (mut env, platform) = setup();
+    let (match_account, _, joiner) = pending_verdict(&mut env, &platform);
+    env.send(&[open_dispute_ix(&match_account, &joiner)]).unwrap();
+
+    let mut same_admin = resolve_dispute_ix(&env, &platform, &match_account, DisputeResolution::Refund);
+    same_admin.accounts.last_mut().unwrap().pubkey = platform.admins[0];
+    let result = env.send(&[same_admin]);
+    assert_eq!(result, Err(program_error(PV3Error::InsufficientAdminSignatures)));
+
+    let outsider = env.wallet(SOL);
+    let mut outsider_signed = resolve_dispute_ix(&env, &platform, &match_account, DisputeResolution::Refund);
+    outsider_signed.accounts.last_mut().unwrap().pubkey = outsider;
+    let result = env.send(&[outsider_signed]);
+    assert_eq!(result, Err(program_error(PV3Error::UnauthorizedAdmin)));
+}
+
+#[test]
+fn upheld_draw_splits_the_pot() {
+    let (mut env, platform) = setup();
+    enable_challenge_period(&mut env, &platform);
+    let (match_account, creator, joiner) = started_match(&mut env);
+    env.send(&verified_draw(&env, &platform, &match_account, RESULT_HASH)).unwrap();
+    env.send(&[open_dispute_ix(&match_account, &creator)]).unwrap();
+    let creator_before = env.lamports(&creator);
+    let joiner_before = env.lamports(&joiner);
+
+    let resolve = resolve_dispute_ix(&env, &platform, &match_account, DisputeResolution::ReverseResult);
+    let result = env.send(&[resolve]);
+    assert_eq!(result, Err(program_error(PV3Error::NoVerdictToArbitrate)));
+
+    let resolve = resolve_dispute_ix(&env, &platform, &match_account, DisputeResolution::UpholdResult);
+    env.send(&[resolve]).unwrap();
+
+    let drawn = &env.events::<MatchDrawn>()[0];
+    assert_eq!(env.lamports(&creator), creator_before + drawn.creator_amount);
+    assert_eq!(env.lamports(&joiner), joiner_before + drawn.joiner_amount);
+    assert_eq!(env.events::<DisputeResolved>()[0].winner, Pubkey::default());
+    assert!(match_state(&env, &match_account).status == MatchStatus::Drawn);
+}
@@ -0,0 +1,99 @@
+use super::*;
+use crate::{Escrow, FundingSource, MatchCompleted, MatchDrawn, MatchStatus, SessionVault};
+
+// Both sides staked from their session vaults
+fn session_funded_match(env: &mut TestEnv) -> (Pubkey, Pubkey, Pubkey) {
+    let creator = player(env);
+    let joiner = player(env);
+    fund_session(env, &creator, SOL);
+    fund_session(env, &joiner, SOL);
+    let options = MatchOptions { use_session_vault: true, ..MatchOptions::default() };
+    let match_account = create_match(env, &creator, &options);
+    env.send(&[join_match_ix(&match_account, &joiner, true, None)]).unwrap();
+    (match_account, creator, joiner)
+}
+
+fn vault_balance(env: &TestEnv, owner: &Pubkey) -> u64 {
+    env.get::<SessionVault>(&session_pda(owner)).balance
+}
+
+#[test]
+fn escrow_ledger_tracks_every_stake_until_payout() {
+    let (mut env, platform) = setup();
+    let creator = player(&mut env);
+    let joiner = player(&mut env);
+    let match_account = create_match(&mut env, &creator, &MatchOptions::default());
+    let escrow = escrow_pda(&match_account);
+    let escrow_rent = env.lamports(&escrow) - WAGER;
+    assert_eq!(env.get::<Escrow>(&escrow).balance, WAGER);
+
+    env.send(&[join_match_ix(&match_account, &joiner, false, None)]).unwrap();
+    assert_eq!(env.get::<Escrow>(&escrow).balance, 2 * WAGER);
+    assert_eq!(env.lamports(&escrow), escrow_rent + 2 * WAGER);
+
+    env.send(&verified_result(&platform, &match_account, &creator, [1; 32])).unwrap();
+
+    let completed = &env.events::<MatchCompleted>()[0];
+    assert_eq!(completed.winner_amount + completed.platform_fee, 2 * WAGER);
+    assert_eq!(env.get::<Escrow>(&escrow).balance, 0);
+    assert_eq!(env.lamports(&escrow), escrow_rent);
+}
+
+#[test]
+fn session_stakes_move_into_escrow_and_record_their_source() {
+    let (mut env, _) = setup();
+    let (match_account, creator, joiner) = session_funded_match(&mut env);
+
+    let state = match_state(&env, &match_account);
+    assert!(state.creator_funding == FundingSource::SessionVault);
+    assert!(state.joiner_funding == FundingSource::SessionVault);
+    assert_eq!(vault_balance(&env, &creator), SOL - WAGER);
+    assert_eq!(vault_balance(&env, &joiner), SOL - WAGER);
+}
+
+#[test]
+fn expired_refund_returns_each_stake_to_its_session_vault() {
+    let (mut env, _) = setup();
+    let (match_account, creator, joiner) = session_funded_match(&mut env);
+    let keeper = env.wallet(SOL);
+    let creator_wallet = env.lamports(&creator);
+    let joiner_vault_lamports = env.lamports(&session_pda(&joiner));
+
+    env.warp(RESULT_WINDOW + 1);
+    env.send(&[refund_match_ix(&env, &match_account, &keeper)]).unwrap();
+
+    assert_eq!(vault_balance(&env, &creator), SOL);
+    assert_eq!(vault_balance(&env, &joiner), SOL);
+    assert_eq!(env.lamports(&session_pda(&joiner)), joiner_vault_lamports + WAGER);
+    assert_eq!(env.lamports(&creator), creator_wallet);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Refunded);
+}
+
+#[test]
+fn refund_of_a_session_stake_requires_its_vault() {
+    let (mut env, _) = setup();
+    let (match_account, _, _) = session_funded_match(&mut env);
+    let keeper = env.wallet(SOL);
+    let mut refund = refund_match_ix(&env, &match_account, &keeper);
+    // Drop the creator's vault: optional accounts are replaced by the program id
+    refund.accounts[4].pubkey = crate::ID;
+    refund.accounts[4].is_writable = false;
+
+    env.warp(RESULT_WINDOW + 1);
+    let result = env.send(&[refund]);
+
+    assert_eq!(result, Err(program_error(PV3Error::SessionVaultRequired)));
+}
+
+#[test]
+fn draw_shares_return_to_each_session_vault() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, joiner) = session_funded_match(&mut env);
+
+    env.send(&verified_draw(&env, &platform, &match_account, [5; 32])).unwrap();
+
+    let drawn = &env.events::<MatchDrawn>()[0];
+    assert_eq!(vault_balance(&env, &creator), SOL - WAGER + drawn.creator_amount);
+    assert_eq!(vault_balance(&env, &joiner), SOL - WAGER + drawn.joiner_amount);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Drawn);
+}
@@ -0,0 +1,476 @@
+//! In-process runtime for the program tests. Transactions run through the program
+//! entrypoint against an in-memory account store serialized in the runtime's input
+//! format, with syscall stubs for the clock, rent, event logs and system program CPIs.
+//! A failed transaction rolls back every account it touched, and the read-only and
+//! ownership rules the runtime enforces after each instruction are checked here too.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::sync::Once;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    bpf_loader_upgradeable, ed25519_program,
+    entrypoint::{deserialize, ProgramResult, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER},
+    instruction::Instruction,
+    program_error::ProgramError,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    system_program,
+    sysvar::{
+        self,
+        instructions::{self, BorrowedAccountMeta, BorrowedInstruction},
+    },
+};
+use anchor_lang::Discriminator;
+
+// Returned when an instruction changes an account the runtime would not let it change
+pub const READONLY_MODIFIED: ProgramError = ProgramError::Immutable;
+pub const EXTERNAL_ACCOUNT_MODIFIED: ProgramError = ProgramError::IllegalOwner;
+
+const START_TIME: i64 = 1_700_000_000;
+
+thread_local! {
+    static NOW: Cell<i64> = const { Cell::new(START_TIME) };
+    static EVENTS: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+}
+
+static INSTALL_STUBS: Once = Once::new();
+
+struct TestStubs;
+
+impl SyscallStubs for TestStubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        EVENTS.with(|events| events.borrow_mut().push(fields.concat()));
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock {
+            unix_timestamp: NOW.with(Cell::get),
+            ..Clock::default()
+        };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        0
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        0
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        invoke_system_program(instruction, account_infos, signers_seeds)
+    }
+}
+
+// The program only ever calls the system program, for transfers and account creation
+fn invoke_system_program(
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    assert_eq!(instruction.program_id, system_program::ID, "unexpected CPI target");
+
+    let account = |position: usize| {
+        let key = instruction.accounts[position].pubkey;
+        account_infos
+            .iter()
+            .find(|info| *info.key == key)
+            .ok_or(ProgramError::NotEnoughAccountKeys)
+    };
+    let signed = |info: &AccountInfo| {
+        info.is_signer
+            || signers_seeds.iter().any(|seeds| {
+                Pubkey::create_program_address(seeds, &crate::ID).ok() == Some(*info.key)
+            })
+    };
+    let data = &instruction.data;
+    let read_u64 = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+    let read_pubkey = |offset: usize| Pubkey::try_from(&data[offset..offset + 32]).unwrap();
+
+    match u32::from_le_bytes(data[0..4].try_into().unwrap()) {
+        // CreateAccount { lamports, space, owner }
+        0 => {
+            let (from, to) = (account(0)?, account(1)?);
+            if !signed(from) || !signed(to) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if to.lamports() > 0 || !to.data_is_empty() || *to.owner != system_program::ID {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            move_lamports(from, to, read_u64(4))?;
+            to.resize(read_u64(12) as usize)?;
+            to.assign(&read_pubkey(20));
+        }
+        // Assign { owner }
+        1 => {
+            let info = account(0)?;
+            if !signed(info) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            info.assign(&read_pubkey(4));
+        }
+        // Transfer { lamports }
+        2 => {
+            let (from, to) = (account(0)?, account(1)?);
+            if !signed(from) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if *from.owner != system_program::ID || !from.data_is_empty() {
+                return Err(ProgramError::InvalidArgument);
+            }
+            move_lamports(from, to, read_u64(4))?;
+        }
+        // Allocate { space }
+        8 => {
+            let info = account(0)?;
+            if !signed(info) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            info.resize(read_u64(4) as usize)?;
+        }
+        tag => panic!("unsupported system instruction {tag}"),
+    }
+
+    Ok(())
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> ProgramResult {
+    let mut from_lamports = from.try_borrow_mut_lamports()?;
+    if **from_lamports < amount {
+        return Err(ProgramError::InsufficientFunds);
+    }
+    **from_lamports -= amount;
+    drop(from_lamports);
+    **to.try_borrow_mut_lamports()? += amount;
+    Ok(())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestAccount {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+impl TestAccount {
+    fn empty() -> Self {
+        Self {
+            lamports: 0,
+            data: Vec::new(),
+            owner: system_program::ID,
+            executable: false,
+        }
+    }
+}
+
+pub struct TestEnv {
+    accounts: HashMap<Pubkey, TestAccount>,
+    events: Vec<Vec<u8>>,
+    pub now: i64,
+}
+
+impl TestEnv {
+    pub fn new() -> Self {
+        INSTALL_STUBS.call_once(|| {
+            set_syscall_stubs(Box::new(TestStubs));
+        });
+
+        let mut env = Self {
+            accounts: HashMap::new(),
+            events: Vec::new(),
+            now: START_TIME,
+        };
+        for (program_id, loader) in [
+            (system_program::ID, Pubkey::default()),
+            (crate::ID, bpf_loader_upgradeable::ID),
+        ] {
+            env.accounts.insert(program_id, TestAccount {
+                lamports: 1,
+                data: Vec::new(),
+                owner: loader,
+                executable: true,
+            });
+        }
+        env
+    }
+
+    // A system-owned wallet holding the given lamports
+    pub fn wallet(&mut self, lamports: u64) -> Pubkey {
+        let key = Pubkey::new_unique();
+        self.airdrop(&key, lamports);
+        key
+    }
+
+    pub fn airdrop(&mut self, key: &Pubkey, lamports: u64) {
+        self.accounts
+            .entry(*key)
+            .or_insert_with(TestAccount::empty)
+            .lamports += lamports;
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.accounts.get(key).map_or(0, |account| account.lamports)
+    }
+
+    pub fn exists(&self, key: &Pubkey) -> bool {
+        self.accounts.contains_key(key)
+    }
+
+    pub fn account(&self, key: &Pubkey) -> &TestAccount {
+        self.accounts.get(key).expect("account does not exist")
+    }
+
+    pub fn get<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        T::try_deserialize(&mut &self.account(key).data[..]).expect("account does not deserialize")
+    }
+
+    // Rewrites a program account in place, for setting up states no instruction produces
+    pub fn update<T: AccountSerialize + AccountDeserialize>(&mut self, key: &Pubkey, f: impl FnOnce(&mut T)) {
+        let mut value = self.get::<T>(key);
+        f(&mut value);
+        let mut data = Vec::new();
+        value.try_serialize(&mut data).unwrap();
+        let account = self.accounts.get_mut(key).unwrap();
+        account.data[..data.len()].copy_from_slice(&data);
+    }
+
+    pub fn warp(&mut self, seconds: i64) {
+        self.now += seconds;
+    }
+
+    // Events of type T emitted by the last transaction, in emission order. A failed
+    // transaction keeps what it emitted before failing, as its logs do on chain
+    pub fn events<T: AnchorDeserialize + Discriminator>(&self) -> Vec<T> {
+        self.events
+            .iter()
+            .filter(|data| data.starts_with(T::DISCRIMINATOR))
+            .map(|data| T::deserialize(&mut &data[T::DISCRIMINATOR.len()..]).unwrap())
+            .collect()
+    }
+
+    // Runs the instructions as one atomic transaction. Ed25519 instructions are taken as
+    // already verified, the way the runtime verifies precompiles before execution
+    pub fn send(&mut self, instructions: &[Instruction]) -> std::result::Result<(), ProgramError> {
+        let snapshot = self.accounts.clone();
+        self.events.clear();
+        NOW.with(|now| now.set(self.now));
+
+        for (index, instruction) in instructions.iter().enumerate() {
+            if instruction.program_id == ed25519_program::ID {
+                continue;
+            }
+            assert_eq!(instruction.program_id, crate::ID, "unexpected top-level program");
+
+            if let Err(error) = self.execute(instructions, index) {
+                self.accounts = snapshot;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    fn execute(&mut self, instructions: &[Instruction], index: usize) -> std::result::Result<(), ProgramError> {
+        let instruction = &instructions[index];
+
+        // Collapse duplicate metas the way the runtime does, merging their privileges
+        let mut unique: Vec<(Pubkey, bool, bool)> = Vec::new();
+        let mut positions = Vec::new();
+        for meta in &instruction.accounts {
+            match unique.iter().position(|(key, _, _)| *key == meta.pubkey) {
+                Some(position) => {
+                    unique[position].1 |= meta.is_signer;
+                    unique[position].2 |= meta.is_writable;
+                    positions.push(position);
+                }
+                None => {
+                    unique.push((meta.pubkey, meta.is_signer, meta.is_writable));
+                    positions.push(unique.len() - 1);
+                }
+            }
+        }
+
+        let pre: Vec<TestAccount> = unique
+            .iter()
+            .map(|(key, _, _)| {
+                if *key == instructions::ID {
+                    instructions_sysvar(instructions, index)
+                } else {
+                    self.accounts.get(key).cloned().unwrap_or_else(TestAccount::empty)
+                }
+            })
+            .collect();
+
+        let mut input = serialize_input(instruction, &unique, &positions, &pre);
+
+        EVENTS.with(|events| events.borrow_mut().clear());
+        let (result, post) = unsafe {
+            let (program_id, account_infos, data) = deserialize(input.as_mut_ptr() as *mut u8);
+            let result = crate::entry(program_id, &account_infos, data);
+            let post: Vec<TestAccount> = (0..unique.len())
+                .map(|position| {
+                    let meta_index = positions.iter().position(|p| *p == position).unwrap();
+                    let info = &account_infos[meta_index];
+                    TestAccount {
+                        lamports: info.lamports(),
+                        data: info.data.borrow().to_vec(),
+                        owner: *info.owner,
+                        executable: info.executable,
+                    }
+                })
+                .collect();
+            (result, post)
+        };
+        self.events.extend(EVENTS.with(|events| events.take()));
+        result?;
+
+        let pre_total: u128 = pre.iter().map(|account| account.lamports as u128).sum();
+        let post_total: u128 = post.iter().map(|account| account.lamports as u128).sum();
+        assert_eq!(pre_total, post_total, "instruction created or destroyed lamports");
+
+        for (((_, is_signer, is_writable), before), after) in unique.iter().zip(&pre).zip(&post) {
+            if before == after {
+                continue;
+            }
+            if !is_writable {
+                return Err(READONLY_MODIFIED);
+            }
+            // Only the owner may shrink an account's lamports or touch its data; wallets
+            // are only ever debited by system transfers, which need their signature
+            let owned = before.owner == crate::ID;
+            let system_account = before.owner == system_program::ID && before.data.is_empty();
+            if after.data != before.data && !owned && !system_account {
+                return Err(EXTERNAL_ACCOUNT_MODIFIED);
+            }
+            if after.lamports < before.lamports && !owned && !(system_account && *is_signer) {
+                return Err(EXTERNAL_ACCOUNT_MODIFIED);
+            }
+        }
+
+        for ((key, _, _), after) in unique.iter().zip(post) {
+            if *key == instructions::ID {
+                continue;
+            }
+            if after.lamports == 0 {
+                self.accounts.remove(key);
+            } else {
+                self.accounts.insert(*key, after);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn instructions_sysvar(instructions: &[Instruction], index: usize) -> TestAccount {
+    let borrowed: Vec<BorrowedInstruction> = instructions
+        .iter()
+        .map(|instruction| BorrowedInstruction {
+            program_id: &instruction.program_id,
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|meta| BorrowedAccountMeta {
+                    pubkey: &meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: &instruction.data,
+        })
+        .collect();
+    let mut data = instructions::construct_instructions_data(&borrowed);
+    instructions::store_current_index(&mut data, index as u16);
+
+    TestAccount {
+        lamports: 1,
+        data,
+        owner: sysvar::ID,
+        executable: false,
+    }
+}
+
+// Lays the accounts out exactly as the loader does for an aligned program, into an
+// 8-byte aligned buffer so the entrypoint's deserializer can read it in place
+fn serialize_input(
+    instruction: &Instruction,
+    unique: &[(Pubkey, bool, bool)],
+    positions: &[usize],
+    accounts: &[TestAccount],
+) -> Vec<u64> {
+    let mut bytes: Vec<u8> = Vec::new();
+    bytes.extend_from_slice(&(positions.len() as u64).to_le_bytes());
+
+    let mut first_index = vec![None; unique.len()];
+    for (meta_index, position) in positions.iter().enumerate() {
+        if let Some(original) = first_index[*position] {
+            bytes.push(original as u8);
+            bytes.extend_from_slice(&[0u8; 7]);
+            continue;
+        }
+        first_index[*position] = Some(meta_index);
+
+        let (key, is_signer, is_writable) = unique[*position];
+        let account = &accounts[*position];
+        bytes.push(NON_DUP_MARKER);
+        bytes.push(is_signer as u8);
+        bytes.push(is_writable as u8);
+        bytes.push(account.executable as u8);
+        bytes.extend_from_slice(&[0u8; 4]);
+        bytes.extend_from_slice(key.as_ref());
+        bytes.extend_from_slice(account.owner.as_ref());
+        bytes.extend_from_slice(&account.lamports.to_le_bytes());
+        bytes.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&account.data);
+        bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+        bytes.resize(bytes.len().next_multiple_of(8), 0);
+        bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+    }
+
+    bytes.extend_from_slice(&(instruction.data.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&instruction.data);
+    bytes.extend_from_slice(instruction.program_id.as_ref());
+
+    let mut input = vec![0u64; bytes.len().div_ceil(8)];
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), input.as_mut_ptr() as *mut u8, bytes.len());
+    }
+    input
+}
+
+// Builds a single-signature ed25519 precompile instruction in the layout produced by
+// the SDK: count, padding, one offsets struct, then pubkey, signature and message
+pub fn ed25519_instruction(pubkey: &Pubkey, signature: &[u8; 64], message: &[u8]) -> Instruction {
+    const DATA_START: usize = 16;
+    let public_key_offset = DATA_START;
+    let signature_offset = public_key_offset + 32;
+    let message_data_offset = signature_offset + 64;
+
+    let mut data = vec![1u8, 0];
+    for field in [
+        signature_offset as u16,
+        u16::MAX,
+        public_key_offset as u16,
+        u16::MAX,
+        message_data_offset as u16,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(pubkey.as_ref());
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+
+    Instruction {
+        program_id: ed25519_program::ID,
+        accounts: Vec::new(),
+        data,
+    }
+}
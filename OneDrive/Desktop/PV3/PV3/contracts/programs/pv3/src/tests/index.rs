@@ -0,0 +1,84 @@
+use super::*;
+use crate::{MatchIndex, MatchIndexed, PlatformConfig};
+
+#[test]
+fn matches_get_sequential_numbers() {
+    let (mut env, _) = setup();
+    let creator = player(&mut env);
+    let first_number = env.get::<PlatformConfig>(&config_pda()).next_match_number;
+
+    let first = create_match(&mut env, &creator, &MatchOptions::default());
+    let second = create_match(&mut env, &creator, &MatchOptions::default());
+
+    assert_eq!(match_state(&env, &first).match_number, first_number);
+    assert_eq!(match_state(&env, &second).match_number, first_number + 1);
+}
+
+#[test]
+fn creates_prepared_against_the_same_config_both_land() {
+    let (mut env, _) = setup();
+    let first_creator = player(&mut env);
+    let second_creator = player(&mut env);
+    let first = create_match_ix(&env, &first_creator, &MatchOptions::default());
+    let second = create_match_ix(&env, &second_creator, &MatchOptions::default());
+
+    env.send(&[first]).unwrap();
+    env.send(&[second]).unwrap();
+
+    assert_eq!(env.get::<PlatformConfig>(&config_pda()).next_match_number, 3);
+}
+
+#[test]
+fn index_resolves_a_number_to_its_match() {
+    let (mut env, _) = setup();
+    let (match_account, _, _) = started_match(&mut env);
+    let match_number = match_state(&env, &match_account).match_number;
+
+    env.send(&[index_match_ix(&env, &match_account)]).unwrap();
+
+    let index = env.get::<MatchIndex>(&match_index_pda(match_number));
+    assert_eq!(index.match_number, match_number);
+    assert_eq!(index.match_account, match_account);
+    assert_eq!(env.events::<MatchIndexed>()[0].match_account, match_account);
+}
+
+#[test]
+fn only_the_creator_can_index_a_match() {
+    let (mut env, _) = setup();
+    let (match_account, _, joiner) = started_match(&mut env);
+    let mut instruction = index_match_ix(&env, &match_account);
+    instruction.accounts[2].pubkey = joiner;
+
+    let result = env.send(&[instruction]);
+
+    assert_eq!(result, Err(program_error(PV3Error::NotMatchCreator)));
+}
+
+#[test]
+fn closing_a_match_closes_its_index() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, _) = started_match(&mut env);
+    let match_number = match_state(&env, &match_account).match_number;
+    env.send(&[index_match_ix(&env, &match_account)]).unwrap();
+    env.send(&verified_result(&platform, &match_account, &creator, [1; 32])).unwrap();
+    let index_rent = env.lamports(&match_index_pda(match_number));
+    let rent = env.lamports(&match_account) + env.lamports(&escrow_pda(&match_account));
+    let balance_before = env.lamports(&creator);
+
+    env.send(&[close_match_ix(&env, &match_account)]).unwrap();
+
+    assert!(!env.exists(&match_index_pda(match_number)));
+    assert!(!env.exists(&match_account));
+    assert_eq!(env.lamports(&creator), balance_before + rent + index_rent);
+}
+
+#[test]
+fn unindexed_match_can_still_be_closed() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, _) = started_match(&mut env);
+    env.send(&verified_result(&platform, &match_account, &creator, [1; 32])).unwrap();
+
+    env.send(&[close_match_ix(&env, &match_account)]).unwrap();
+
+    assert!(!env.exists(&match_account));
+}
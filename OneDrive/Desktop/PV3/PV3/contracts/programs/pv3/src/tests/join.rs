@@ -0,0 +1,119 @@
+use super::*;
+use crate::{MatchStatus, PlayerReady, ReadyCheckFailed};
+use anchor_lang::solana_program::hash::hash;
+
+const READY_WINDOW: i64 = 5 * 60;
+const SECRET: &[u8] = b"open sesame";
+
+fn ready_ix(match_account: &Pubkey, player: &Pubkey) -> Instruction {
+    ix(
+        crate::accounts::Ready {
+            match_account: *match_account,
+            player: *player,
+        },
+        crate::instruction::Ready {},
+    )
+}
+
+// A joined match waiting on both players' ready check
+fn awaiting_ready_match(env: &mut TestEnv) -> (Pubkey, Pubkey, Pubkey) {
+    let creator = player(env);
+    let joiner = player(env);
+    let options = MatchOptions { ready_window: READY_WINDOW, ..MatchOptions::default() };
+    let match_account = create_match(env, &creator, &options);
+    env.send(&[join_match_ix(&match_account, &joiner, false, None)]).unwrap();
+    (match_account, creator, joiner)
+}
+
+#[test]
+fn only_the_invited_opponent_can_join() {
+    let (mut env, _) = setup();
+    let creator = player(&mut env);
+    let invited = player(&mut env);
+    let stranger = player(&mut env);
+    let options = MatchOptions { invited_opponent: Some(invited), ..MatchOptions::default() };
+    let match_account = create_match(&mut env, &creator, &options);
+
+    let result = env.send(&[join_match_ix(&match_account, &stranger, false, None)]);
+    assert_eq!(result, Err(program_error(PV3Error::NotInvitedOpponent)));
+
+    env.send(&[join_match_ix(&match_account, &invited, false, None)]).unwrap();
+    assert_eq!(match_state(&env, &match_account).joiner, invited);
+}
+
+#[test]
+fn secret_matches_need_the_preimage_of_the_join_secret_hash() {
+    let (mut env, _) = setup();
+    let creator = player(&mut env);
+    let joiner = player(&mut env);
+    let options = MatchOptions {
+        join_secret_hash: Some(hash(SECRET).to_bytes()),
+        ..MatchOptions::default()
+    };
+    let match_account = create_match(&mut env, &creator, &options);
+
+    for join_secret in [None, Some(b"wrong guess".to_vec())] {
+        let result = env.send(&[join_match_ix(&match_account, &joiner, false, join_secret)]);
+        assert_eq!(result, Err(program_error(PV3Error::InvalidJoinSecret)));
+    }
+
+    env.send(&[join_match_ix(&match_account, &joiner, false, Some(SECRET.to_vec()))]).unwrap();
+    assert!(match_state(&env, &match_account).status == MatchStatus::InProgress);
+}
+
+#[test]
+fn game_starts_once_both_players_are_ready() {
+    let (mut env, _) = setup();
+    let (match_account, creator, joiner) = awaiting_ready_match(&mut env);
+    assert!(match_state(&env, &match_account).status == MatchStatus::AwaitingReady);
+
+    env.send(&[ready_ix(&match_account, &creator)]).unwrap();
+    assert!(!env.events::<PlayerReady>()[0].started);
+    let result = env.send(&[ready_ix(&match_account, &creator)]);
+    assert_eq!(result, Err(program_error(PV3Error::AlreadyReady)));
+
+    env.send(&[ready_ix(&match_account, &joiner)]).unwrap();
+
+    let ready = &env.events::<PlayerReady>()[0];
+    assert!(ready.started);
+    assert_eq!(ready.result_deadline, env.now + RESULT_WINDOW);
+    assert!(match_state(&env, &match_account).status == MatchStatus::InProgress);
+}
+
+#[test]
+fn ready_check_closes_at_its_deadline() {
+    let (mut env, _) = setup();
+    let (match_account, creator, _) = awaiting_ready_match(&mut env);
+    let stranger = player(&mut env);
+
+    let result = env.send(&[ready_ix(&match_account, &stranger)]);
+    assert_eq!(result, Err(program_error(PV3Error::InvalidParticipant)));
+
+    env.warp(READY_WINDOW + 1);
+    let result = env.send(&[ready_ix(&match_account, &creator)]);
+    assert_eq!(result, Err(program_error(PV3Error::ReadyWindowClosed)));
+}
+
+#[test]
+fn missed_ready_check_refunds_both_players_and_records_the_no_show() {
+    let (mut env, _) = setup();
+    let (match_account, creator, joiner) = awaiting_ready_match(&mut env);
+    let keeper = env.wallet(SOL);
+    env.send(&[ready_ix(&match_account, &creator)]).unwrap();
+
+    let result = env.send(&[refund_match_ix(&env, &match_account, &keeper)]);
+    assert_eq!(result, Err(program_error(PV3Error::ReadyDeadlineNotReached)));
+
+    env.warp(READY_WINDOW + 1);
+    let creator_before = env.lamports(&creator);
+    let joiner_before = env.lamports(&joiner);
+    env.send(&[refund_match_ix(&env, &match_account, &keeper)]).unwrap();
+
+    let failed = &env.events::<ReadyCheckFailed>()[0];
+    assert!(!failed.creator_no_show && failed.joiner_no_show);
+    assert_eq!(env.lamports(&creator), creator_before + WAGER);
+    assert_eq!(env.lamports(&joiner), joiner_before + WAGER);
+    let state = match_state(&env, &match_account);
+    assert!(state.status == MatchStatus::Refunded);
+    assert!(state.joiner_no_show);
+}
@@ -0,0 +1,421 @@
+use super::*;
+use crate::{
+    FlaggedLobbyResolved, FundingSource, Lobby, LobbyCompleted, LobbyResultPending, MatchStatus,
+    SessionVault,
+};
+
+const RESULT_HASH: [u8; 32] = [3; 32];
+
+fn lobby_pda(creator: &Pubkey, nonce: u64) -> Pubkey {
+    pda(&[b"lobby", creator.as_ref(), &nonce.to_le_bytes()])
+}
+
+fn create_lobby(
+    env: &mut TestEnv,
+    creator: &Pubkey,
+    max_players: u8,
+    payout_bps: Vec<u16>,
+    use_session_vault: bool,
+) -> Pubkey {
+    let nonce = env.get::<crate::PlayerProfile>(&profile_pda(creator)).match_nonce;
+    let lobby = lobby_pda(creator, nonce);
+    env.send(&[ix(
+        crate::accounts::CreateLobby {
+            lobby,
+            creator_profile: profile_pda(creator),
+            game_config: game_config_pda(GAME_ID),
+            lobby_escrow: escrow_pda(&lobby),
+            session_vault: use_session_vault.then(|| session_pda(creator)),
+            config: config_pda(),
+            creator: *creator,
+            system_program: system_program::ID,
+        },
+        crate::instruction::CreateLobby {
+            nonce,
+            game_id: GAME_ID.to_string(),
+            wager_amount: WAGER,
+            max_players,
+            payout_bps,
+            join_deadline: env.now + JOIN_WINDOW,
+            result_window: RESULT_WINDOW,
+            use_session_vault,
+        },
+    )])
+    .unwrap();
+    lobby
+}
+
+fn join_lobby(env: &mut TestEnv, lobby: &Pubkey, player: &Pubkey, use_session_vault: bool) {
+    env.send(&[ix(
+        crate::accounts::JoinLobby {
+            lobby: *lobby,
+            lobby_escrow: escrow_pda(lobby),
+            session_vault: use_session_vault.then(|| session_pda(player)),
+            config: config_pda(),
+            player: *player,
+            system_program: system_program::ID,
+        },
+        crate::instruction::JoinLobby { use_session_vault },
+    )])
+    .unwrap();
+}
+
+// Where a player's share goes back to: their wallet, or the vault that funded them
+fn payout_target(env: &TestEnv, lobby: &Pubkey, player: &Pubkey) -> Pubkey {
+    let state = env.get::<Lobby>(lobby);
+    let funding_source = state.funding_of(player).unwrap();
+    funding_vault(funding_source, player).unwrap_or(*player)
+}
+
+fn submit_lobby_result(
+    platform: &Platform,
+    lobby: &Pubkey,
+    ranking: &[Pubkey],
+    recipients: &[Pubkey],
+) -> Vec<Instruction> {
+    let message = crate::lobby::create_lobby_result_message(*lobby, ranking, RESULT_HASH);
+    let (signature, ed25519) = signed_by_verifier(platform, &message);
+    let submit = ix(
+        crate::accounts::SubmitLobbyResult {
+            lobby: *lobby,
+            lobby_escrow: escrow_pda(lobby),
+            treasury: platform.treasury,
+            referral_pool: platform.referral_pool,
+            config: config_pda(),
+            instruction_sysvar: anchor_lang::solana_program::sysvar::instructions::ID,
+        },
+        crate::instruction::SubmitLobbyResult {
+            ranking: ranking.to_vec(),
+            result_hash: RESULT_HASH,
+            ed25519_signature: signature,
+            ed25519_instruction_index: 1,
+        },
+    );
+    vec![with_targets(submit, recipients), ed25519]
+}
+
+fn refund_lobby_ix(lobby: &Pubkey, targets: &[Pubkey]) -> Instruction {
+    with_targets(
+        ix(
+            crate::accounts::RefundLobby {
+                lobby: *lobby,
+                lobby_escrow: escrow_pda(lobby),
+            },
+            crate::instruction::RefundLobby {},
+        ),
+        targets,
+    )
+}
+
+fn finalize_lobby_ix(platform: &Platform, lobby: &Pubkey, recipients: &[Pubkey]) -> Instruction {
+    with_targets(
+        ix(
+            crate::accounts::FinalizeLobby {
+                lobby: *lobby,
+                lobby_escrow: escrow_pda(lobby),
+                treasury: platform.treasury,
+                referral_pool: platform.referral_pool,
+                config: config_pda(),
+            },
+            crate::instruction::FinalizeLobby {},
+        ),
+        recipients,
+    )
+}
+
+fn flag_lobby_ix(lobby: &Pubkey, flagger: &Pubkey) -> Instruction {
+    ix(
+        crate::accounts::FlagLobby {
+            lobby: *lobby,
+            config: config_pda(),
+            flagger: *flagger,
+        },
+        crate::instruction::FlagLobby {},
+    )
+}
+
+fn resolve_flagged_lobby_ix(
+    platform: &Platform,
+    lobby: &Pubkey,
+    release_payout: bool,
+    targets: &[Pubkey],
+) -> Instruction {
+    with_targets(
+        ix(
+            crate::accounts::ResolveFlaggedLobby {
+                lobby: *lobby,
+                lobby_escrow: escrow_pda(lobby),
+                treasury: platform.treasury,
+                referral_pool: platform.referral_pool,
+                config: config_pda(),
+                admin1: platform.admins[0],
+                admin2: platform.admins[1],
+            },
+            crate::instruction::ResolveFlaggedLobby { release_payout },
+        ),
+        targets,
+    )
+}
+
+fn start_lobby_ix(lobby: &Pubkey, creator: &Pubkey) -> Instruction {
+    ix(
+        crate::accounts::StartLobby {
+            lobby: *lobby,
+            creator: *creator,
+        },
+        crate::instruction::StartLobby {},
+    )
+}
+
+fn close_lobby_ix(env: &TestEnv, lobby: &Pubkey) -> Instruction {
+    let state = env.get::<Lobby>(lobby);
+    ix(
+        crate::accounts::CloseLobby {
+            lobby: *lobby,
+            lobby_escrow: escrow_pda(lobby),
+            match_index: match_index_pda(state.match_number),
+            creator: state.creator,
+        },
+        crate::instruction::CloseLobby {},
+    )
+}
+
+// Three entrants where the creator staked from their session vault
+fn started_lobby(env: &mut TestEnv) -> (Pubkey, [Pubkey; 3]) {
+    let players = [player(env), player(env), player(env)];
+    fund_session(env, &players[0], SOL);
+    let lobby = create_lobby(env, &players[0], 3, vec![7000, 3000], true);
+    join_lobby(env, &lobby, &players[1], false);
+    join_lobby(env, &lobby, &players[2], false);
+    (lobby, players)
+}
+
+#[test]
+fn entries_record_their_funding_source() {
+    let (mut env, _) = setup();
+    let (lobby, _) = started_lobby(&mut env);
+
+    let state = env.get::<Lobby>(&lobby);
+    assert!(state.status == MatchStatus::InProgress);
+    assert!(
+        state.player_funding
+            == vec![FundingSource::SessionVault, FundingSource::Wallet, FundingSource::Wallet]
+    );
+}
+
+#[test]
+fn ranked_payouts_go_back_to_each_winners_funding_source() {
+    let (mut env, platform) = setup();
+    let (lobby, players) = started_lobby(&mut env);
+    let ranking = [players[0], players[2], players[1]];
+    let vault_before = env.get::<SessionVault>(&session_pda(&players[0])).balance;
+    let second_before = env.lamports(&players[2]);
+
+    let recipients = [payout_target(&env, &lobby, &ranking[0]), ranking[1]];
+    env.send(&submit_lobby_result(&platform, &lobby, &ranking, &recipients)).unwrap();
+
+    let completed = &env.events::<LobbyCompleted>()[0];
+    let vault = env.get::<SessionVault>(&session_pda(&players[0]));
+    assert_eq!(vault.balance, vault_before + completed.payouts[0]);
+    assert_eq!(env.lamports(&players[2]), second_before + completed.payouts[1]);
+    assert!(env.get::<Lobby>(&lobby).status == MatchStatus::Completed);
+}
+
+#[test]
+fn ranking_must_list_every_entrant() {
+    let (mut env, platform) = setup();
+    let (lobby, players) = started_lobby(&mut env);
+    let ranking = [players[1], players[2]];
+
+    let result = env.send(&submit_lobby_result(&platform, &lobby, &ranking, &ranking));
+
+    assert_eq!(result, Err(program_error(PV3Error::InvalidRanking)));
+}
+
+#[test]
+fn session_funded_winner_cannot_be_paid_to_their_wallet() {
+    let (mut env, platform) = setup();
+    let (lobby, players) = started_lobby(&mut env);
+    let ranking = [players[0], players[1], players[2]];
+
+    let result = env.send(&submit_lobby_result(&platform, &lobby, &ranking, &ranking[..2]));
+
+    assert_eq!(result, Err(anchor_error(anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram)));
+}
+
+#[test]
+fn expired_lobby_refunds_session_entries_into_their_vaults() {
+    let (mut env, _) = setup();
+    let (lobby, players) = started_lobby(&mut env);
+    let vault_before = env.get::<SessionVault>(&session_pda(&players[0]));
+    let vault_lamports_before = env.lamports(&session_pda(&players[0]));
+    let wallet_before = env.lamports(&players[1]);
+    let targets: Vec<Pubkey> = players.iter().map(|p| payout_target(&env, &lobby, p)).collect();
+
+    let result = env.send(&[refund_lobby_ix(&lobby, &targets)]);
+    assert_eq!(result, Err(program_error(PV3Error::ResultDeadlineNotReached)));
+
+    env.warp(RESULT_WINDOW + 1);
+    env.send(&[refund_lobby_ix(&lobby, &targets)]).unwrap();
+
+    let vault = env.get::<SessionVault>(&session_pda(&players[0]));
+    assert_eq!(vault.balance, vault_before.balance + WAGER);
+    assert_eq!(env.lamports(&session_pda(&players[0])), vault_lamports_before + WAGER);
+    assert_eq!(env.lamports(&players[1]), wallet_before + WAGER);
+    assert!(env.get::<Lobby>(&lobby).status == MatchStatus::Refunded);
+}
+
+#[test]
+fn creator_can_start_a_partly_filled_lobby_until_the_join_deadline() {
+    let (mut env, _) = setup();
+    let players = [player(&mut env), player(&mut env)];
+    let lobby = create_lobby(&mut env, &players[0], 4, vec![10000], false);
+    join_lobby(&mut env, &lobby, &players[1], false);
+
+    env.warp(JOIN_WINDOW + 1);
+    let result = env.send(&[start_lobby_ix(&lobby, &players[0])]);
+    assert_eq!(result, Err(program_error(PV3Error::MatchExpired)));
+
+    env.warp(-1);
+    env.send(&[start_lobby_ix(&lobby, &players[0])]).unwrap();
+    assert!(env.get::<Lobby>(&lobby).status == MatchStatus::InProgress);
+}
+
+#[test]
+fn refund_rejects_another_players_vault() {
+    let (mut env, _) = setup();
+    let (lobby, players) = started_lobby(&mut env);
+    let stranger = player(&mut env);
+    let strangers_vault = fund_session(&mut env, &stranger, SOL);
+
+    env.warp(RESULT_WINDOW + 1);
+    let result = env.send(&[refund_lobby_ix(&lobby, &[strangers_vault, players[1], players[2]])]);
+
+    assert_eq!(result, Err(program_error(PV3Error::InvalidParticipant)));
+}
+
+#[test]
+fn ranking_waits_out_the_challenge_period() {
+    let (mut env, platform) = setup();
+    enable_challenge_period(&mut env, &platform);
+    let (lobby, players) = started_lobby(&mut env);
+    let ranking = [players[1], players[2], players[0]];
+    let first_before = env.lamports(&players[1]);
+
+    env.send(&submit_lobby_result(&platform, &lobby, &ranking, &[])).unwrap();
+
+    let pending = &env.events::<LobbyResultPending>()[0];
+    assert!(pending.ranking == ranking.to_vec());
+    assert!(env.get::<Lobby>(&lobby).status == MatchStatus::PendingSettlement);
+    assert_eq!(env.lamports(&players[1]), first_before);
+
+    let recipients = [ranking[0], ranking[1]];
+    let result = env.send(&[finalize_lobby_ix(&platform, &lobby, &recipients)]);
+    assert_eq!(result, Err(program_error(PV3Error::ChallengePeriodActive)));
+
+    env.warp(CHALLENGE_PERIOD);
+    env.send(&[finalize_lobby_ix(&platform, &lobby, &recipients)]).unwrap();
+
+    let completed = &env.events::<LobbyCompleted>()[0];
+    assert_eq!(env.lamports(&players[1]), first_before + completed.payouts[0]);
+    assert!(env.get::<Lobby>(&lobby).status == MatchStatus::Completed);
+}
+
+#[test]
+fn flagged_ranking_can_be_voided_back_to_funding_sources() {
+    let (mut env, platform) = setup();
+    enable_challenge_period(&mut env, &platform);
+    let (lobby, players) = started_lobby(&mut env);
+    let stranger = player(&mut env);
+    let ranking = [players[1], players[2], players[0]];
+    env.send(&submit_lobby_result(&platform, &lobby, &ranking, &[])).unwrap();
+
+    let result = env.send(&[flag_lobby_ix(&lobby, &stranger)]);
+    assert_eq!(result, Err(program_error(PV3Error::UnauthorizedFlag)));
+
+    env.send(&[flag_lobby_ix(&lobby, &players[0])]).unwrap();
+    env.warp(CHALLENGE_PERIOD);
+    let result = env.send(&[finalize_lobby_ix(&platform, &lobby, &[ranking[0], ranking[1]])]);
+    assert_eq!(result, Err(program_error(PV3Error::MatchFlagged)));
+
+    let vault_before = env.get::<SessionVault>(&session_pda(&players[0])).balance;
+    let targets: Vec<Pubkey> = players.iter().map(|p| payout_target(&env, &lobby, p)).collect();
+    env.send(&[resolve_flagged_lobby_ix(&platform, &lobby, false, &targets)]).unwrap();
+
+    let vault = env.get::<SessionVault>(&session_pda(&players[0]));
+    assert_eq!(vault.balance, vault_before + WAGER);
+    assert!(env.get::<Lobby>(&lobby).status == MatchStatus::Refunded);
+    assert!(!env.events::<FlaggedLobbyResolved>()[0].payout_released);
+}
+
+#[test]
+fn lobby_cannot_be_flagged_after_the_challenge_period() {
+    let (mut env, platform) = setup();
+    enable_challenge_period(&mut env, &platform);
+    let (lobby, players) = started_lobby(&mut env);
+    let ranking = [players[1], players[2], players[0]];
+    env.send(&submit_lobby_result(&platform, &lobby, &ranking, &[])).unwrap();
+
+    env.warp(CHALLENGE_PERIOD);
+    let result = env.send(&[flag_lobby_ix(&lobby, &players[0])]);
+
+    assert_eq!(result, Err(program_error(PV3Error::ChallengePeriodEnded)));
+}
+
+#[test]
+fn lobbies_share_the_match_number_sequence_and_can_be_indexed() {
+    let (mut env, _) = setup();
+    let creator = player(&mut env);
+    let match_account = create_match(&mut env, &creator, &MatchOptions::default());
+    let lobby = create_lobby(&mut env, &creator, 3, vec![10000], false);
+    let match_number = env.get::<Lobby>(&lobby).match_number;
+    assert_eq!(match_number, match_state(&env, &match_account).match_number + 1);
+
+    env.send(&[ix(
+        crate::accounts::IndexLobby {
+            lobby,
+            match_index: match_index_pda(match_number),
+            creator,
+            system_program: system_program::ID,
+        },
+        crate::instruction::IndexLobby {},
+    )])
+    .unwrap();
+
+    let index = env.get::<crate::MatchIndex>(&match_index_pda(match_number));
+    assert_eq!(index.match_account, lobby);
+}
+
+#[test]
+fn refunded_lobby_can_be_closed_with_its_index() {
+    let (mut env, _) = setup();
+    let (lobby, players) = started_lobby(&mut env);
+    let match_number = env.get::<Lobby>(&lobby).match_number;
+    env.send(&[ix(
+        crate::accounts::IndexLobby {
+            lobby,
+            match_index: match_index_pda(match_number),
+            creator: players[0],
+            system_program: system_program::ID,
+        },
+        crate::instruction::IndexLobby {},
+    )])
+    .unwrap();
+
+    let result = env.send(&[close_lobby_ix(&env, &lobby)]);
+    assert_eq!(result, Err(program_error(PV3Error::MatchNotSettled)));
+
+    env.warp(RESULT_WINDOW + 1);
+    let targets: Vec<Pubkey> = players.iter().map(|p| payout_target(&env, &lobby, p)).collect();
+    env.send(&[refund_lobby_ix(&lobby, &targets)]).unwrap();
+    let rent = env.lamports(&lobby)
+        + env.lamports(&escrow_pda(&lobby))
+        + env.lamports(&match_index_pda(match_number));
+    let balance_before = env.lamports(&players[0]);
+
+    env.send(&[close_lobby_ix(&env, &lobby)]).unwrap();
+
+    assert!(!env.exists(&lobby));
+    assert!(!env.exists(&match_index_pda(match_number)));
+    assert_eq!(env.lamports(&players[0]), balance_before + rent);
+}
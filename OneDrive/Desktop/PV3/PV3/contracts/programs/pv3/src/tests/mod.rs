@@ -0,0 +1,576 @@
+//! Program tests. Each file drives real instructions through the entrypoint with the
+//! in-process runtime in `harness`; the fixtures below cover the common setup steps
+
+// Shared fixtures, not every helper is used by every test file
+#![allow(dead_code)]
+
+mod harness;
+
+mod abandonment;
+mod batch;
+mod cancel;
+mod close;
+mod challenge;
+mod crank;
+mod deadlines;
+mod disputes;
+mod funding;
+mod index;
+mod join;
+mod lobby;
+mod outcomes;
+mod rematch;
+mod scores;
+mod series;
+mod team;
+mod signatures;
+mod wagers;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    system_program,
+};
+use anchor_lang::{InstructionData, ToAccountMetas};
+
+pub use harness::*;
+
+use crate::{Match, PV3Error};
+
+pub const SOL: u64 = 1_000_000_000;
+pub const WAGER: u64 = SOL / 10; // Smallest default tier
+pub const RESULT_WINDOW: i64 = 60 * 60;
+pub const JOIN_WINDOW: i64 = 60 * 60;
+pub const GAME_ID: &str = "chess";
+
+pub fn ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn program_error(error: PV3Error) -> ProgramError {
+    anchor_lang::error::Error::from(error).into()
+}
+
+pub fn anchor_error(error: anchor_lang::error::ErrorCode) -> ProgramError {
+    anchor_lang::error::Error::from(error).into()
+}
+
+pub fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &crate::ID).0
+}
+
+pub fn config_pda() -> Pubkey {
+    pda(&[b"config"])
+}
+
+pub fn profile_pda(owner: &Pubkey) -> Pubkey {
+    pda(&[b"profile", owner.as_ref()])
+}
+
+pub fn session_pda(owner: &Pubkey) -> Pubkey {
+    pda(&[b"session", owner.as_ref()])
+}
+
+pub fn escrow_pda(game: &Pubkey) -> Pubkey {
+    pda(&[b"escrow", game.as_ref()])
+}
+
+pub fn match_pda(creator: &Pubkey, nonce: u64) -> Pubkey {
+    pda(&[b"match", creator.as_ref(), &nonce.to_le_bytes()])
+}
+
+pub fn match_index_pda(match_number: u64) -> Pubkey {
+    pda(&[b"match_index", &match_number.to_le_bytes()])
+}
+
+pub fn dispute_pda(match_account: &Pubkey) -> Pubkey {
+    pda(&[b"dispute", match_account.as_ref()])
+}
+
+pub fn game_config_pda(game_id: &str) -> Pubkey {
+    let game_hash = anchor_lang::solana_program::hash::hash(game_id.as_bytes());
+    pda(&[b"game_config", game_hash.as_ref()])
+}
+
+pub struct Platform {
+    pub admins: [Pubkey; 3],
+    pub treasury: Pubkey,
+    pub referral_pool: Pubkey,
+    pub verifier: Pubkey,
+}
+
+// A fresh environment with the platform initialized under its default config
+pub fn setup() -> (TestEnv, Platform) {
+    let mut env = TestEnv::new();
+    let payer = env.wallet(10 * SOL);
+    let platform = Platform {
+        admins: [env.wallet(SOL), env.wallet(SOL), env.wallet(SOL)],
+        treasury: env.wallet(SOL),
+        referral_pool: env.wallet(SOL),
+        verifier: env.wallet(SOL),
+    };
+
+    env.send(&[ix(
+        crate::accounts::Initialize {
+            config: config_pda(),
+            payer,
+            system_program: system_program::ID,
+        },
+        crate::instruction::Initialize {
+            treasury: platform.treasury,
+            referral_pool: platform.referral_pool,
+            verifier_pubkey: platform.verifier,
+            admin1: platform.admins[0],
+            admin2: platform.admins[1],
+            admin3: platform.admins[2],
+        },
+    )])
+    .unwrap();
+
+    (env, platform)
+}
+
+// A funded wallet with its player profile created
+pub fn player(env: &mut TestEnv) -> Pubkey {
+    let player = env.wallet(100 * SOL);
+    env.send(&[ix(
+        crate::accounts::CreateProfile {
+            profile: profile_pda(&player),
+            user: player,
+            system_program: system_program::ID,
+        },
+        crate::instruction::CreateProfile {},
+    )])
+    .unwrap();
+    player
+}
+
+// Opens the player's session vault and deposits into it
+pub fn fund_session(env: &mut TestEnv, owner: &Pubkey, amount: u64) -> Pubkey {
+    let session_vault = session_pda(owner);
+    if !env.exists(&session_vault) {
+        env.send(&[ix(
+            crate::accounts::CreateSession {
+                session_vault,
+                user: *owner,
+                system_program: system_program::ID,
+            },
+            crate::instruction::CreateSession {},
+        )])
+        .unwrap();
+    }
+    env.send(&[ix(
+        crate::accounts::DepositToSession {
+            session_vault,
+            user: *owner,
+            system_program: system_program::ID,
+        },
+        crate::instruction::DepositToSession { amount },
+    )])
+    .unwrap();
+    session_vault
+}
+
+#[derive(Clone)]
+pub struct MatchOptions {
+    pub wager: u64,
+    pub use_session_vault: bool,
+    pub invited_opponent: Option<Pubkey>,
+    pub join_secret_hash: Option<[u8; 32]>,
+    pub series_length: u8,
+    pub is_async: bool,
+    pub ready_window: i64,
+    pub result_window: i64,
+}
+
+impl Default for MatchOptions {
+    fn default() -> Self {
+        Self {
+            wager: WAGER,
+            use_session_vault: false,
+            invited_opponent: None,
+            join_secret_hash: None,
+            series_length: 1,
+            is_async: false,
+            ready_window: 0,
+            result_window: RESULT_WINDOW,
+        }
+    }
+}
+
+pub fn create_match_ix(env: &TestEnv, creator: &Pubkey, options: &MatchOptions) -> Instruction {
+    let nonce = env.get::<crate::PlayerProfile>(&profile_pda(creator)).match_nonce;
+    let match_account = match_pda(creator, nonce);
+
+    ix(
+        crate::accounts::CreateMatch {
+            match_account,
+            creator_profile: profile_pda(creator),
+            game_config: game_config_pda(GAME_ID),
+            match_escrow: escrow_pda(&match_account),
+            session_vault: options.use_session_vault.then(|| session_pda(creator)),
+            config: config_pda(),
+            creator: *creator,
+            system_program: system_program::ID,
+        },
+        crate::instruction::CreateMatch {
+            nonce,
+            game_id: GAME_ID.to_string(),
+            wager_amount: options.wager,
+            join_deadline: env.now + JOIN_WINDOW,
+            result_window: options.result_window,
+            use_session_vault: options.use_session_vault,
+            invited_opponent: options.invited_opponent,
+            join_secret_hash: options.join_secret_hash,
+            series_length: options.series_length,
+            is_async: options.is_async,
+            ready_window: options.ready_window,
+        },
+    )
+}
+
+// Creates a match and returns its address
+pub fn create_match(env: &mut TestEnv, creator: &Pubkey, options: &MatchOptions) -> Pubkey {
+    let instruction = create_match_ix(env, creator, options);
+    let match_account = instruction.accounts[0].pubkey;
+    env.send(&[instruction]).unwrap();
+    match_account
+}
+
+pub fn join_match_ix(
+    match_account: &Pubkey,
+    joiner: &Pubkey,
+    use_session_vault: bool,
+    join_secret: Option<Vec<u8>>,
+) -> Instruction {
+    ix(
+        crate::accounts::JoinMatch {
+            match_account: *match_account,
+            match_escrow: escrow_pda(match_account),
+            session_vault: use_session_vault.then(|| session_pda(joiner)),
+            config: config_pda(),
+            joiner: *joiner,
+            system_program: system_program::ID,
+        },
+        crate::instruction::JoinMatch {
+            use_session_vault,
+            join_secret,
+        },
+    )
+}
+
+// A wallet-funded match between two fresh players that is already in progress
+pub fn started_match(env: &mut TestEnv) -> (Pubkey, Pubkey, Pubkey) {
+    let creator = player(env);
+    let joiner = player(env);
+    let match_account = create_match(env, &creator, &MatchOptions::default());
+    env.send(&[join_match_ix(&match_account, &joiner, false, None)]).unwrap();
+    (match_account, creator, joiner)
+}
+
+pub fn match_state(env: &TestEnv, match_account: &Pubkey) -> Match {
+    env.get::<Match>(match_account)
+}
+
+pub const CHALLENGE_PERIOD: i64 = 60 * 60;
+
+// Holds every verdict for CHALLENGE_PERIOD before it can be finalized
+pub fn enable_challenge_period(env: &mut TestEnv, platform: &Platform) {
+    env.send(&[ix(
+        crate::accounts::EmergencyAction {
+            config: config_pda(),
+            signer: platform.admins[0],
+        },
+        crate::instruction::UpdateChallengePeriod {
+            challenge_period: CHALLENGE_PERIOD,
+        },
+    )])
+    .unwrap();
+}
+
+// The abandoner's profile whenever the match carries an abandonment verdict
+pub fn abandoner_profile(state: &Match) -> Option<Pubkey> {
+    (state.abandoned_by != Pubkey::default()).then(|| profile_pda(&state.abandoned_by))
+}
+
+pub fn finalize_match_ix(env: &TestEnv, platform: &Platform, match_account: &Pubkey) -> Instruction {
+    let state = match_state(env, match_account);
+    ix(
+        crate::accounts::FinalizeMatch {
+            match_account: *match_account,
+            match_escrow: escrow_pda(match_account),
+            winner: state.winner,
+            abandoner_profile: abandoner_profile(&state),
+            treasury: platform.treasury,
+            referral_pool: platform.referral_pool,
+            config: config_pda(),
+        },
+        crate::instruction::FinalizeMatch {},
+    )
+}
+
+pub fn finalize_draw_ix(env: &TestEnv, platform: &Platform, match_account: &Pubkey) -> Instruction {
+    let state = match_state(env, match_account);
+    ix(
+        crate::accounts::FinalizeDraw {
+            match_account: *match_account,
+            match_escrow: escrow_pda(match_account),
+            creator: state.creator,
+            joiner: state.joiner,
+            creator_session_vault: funding_vault(state.creator_funding, &state.creator),
+            joiner_session_vault: funding_vault(state.joiner_funding, &state.joiner),
+            treasury: platform.treasury,
+            referral_pool: platform.referral_pool,
+            config: config_pda(),
+        },
+        crate::instruction::FinalizeDraw {},
+    )
+}
+
+pub fn flag_match_ix(match_account: &Pubkey, flagger: &Pubkey) -> Instruction {
+    ix(
+        crate::accounts::FlagMatch {
+            match_account: *match_account,
+            config: config_pda(),
+            flagger: *flagger,
+        },
+        crate::instruction::FlagMatch {},
+    )
+}
+
+pub fn resolve_flagged_match_ix(
+    env: &TestEnv,
+    platform: &Platform,
+    match_account: &Pubkey,
+    release_payout: bool,
+) -> Instruction {
+    let state = match_state(env, match_account);
+    // A pending draw has no winner, any participant stands in for the unused account
+    let winner = if state.winner == Pubkey::default() { state.creator } else { state.winner };
+    ix(
+        crate::accounts::ResolveFlaggedMatch {
+            match_account: *match_account,
+            match_escrow: escrow_pda(match_account),
+            winner,
+            abandoner_profile: abandoner_profile(&state),
+            creator: state.creator,
+            joiner: state.joiner,
+            creator_session_vault: funding_vault(state.creator_funding, &state.creator),
+            joiner_session_vault: funding_vault(state.joiner_funding, &state.joiner),
+            treasury: platform.treasury,
+            referral_pool: platform.referral_pool,
+            config: config_pda(),
+            admin1: platform.admins[0],
+            admin2: platform.admins[1],
+        },
+        crate::instruction::ResolveFlaggedMatch { release_payout },
+    )
+}
+
+pub fn index_match_ix(env: &TestEnv, match_account: &Pubkey) -> Instruction {
+    let state = match_state(env, match_account);
+    ix(
+        crate::accounts::IndexMatch {
+            match_account: *match_account,
+            match_index: match_index_pda(state.match_number),
+            creator: state.creator,
+            system_program: system_program::ID,
+        },
+        crate::instruction::IndexMatch {},
+    )
+}
+
+// Passes the dispute opener whenever the match was disputed
+pub fn close_match_ix(env: &TestEnv, match_account: &Pubkey) -> Instruction {
+    let state = match_state(env, match_account);
+    let dispute = dispute_pda(match_account);
+    let dispute_opener = env
+        .exists(&dispute)
+        .then(|| env.get::<crate::Dispute>(&dispute).opened_by);
+    ix(
+        crate::accounts::CloseMatch {
+            match_account: *match_account,
+            match_escrow: escrow_pda(match_account),
+            match_index: match_index_pda(state.match_number),
+            dispute,
+            dispute_opener,
+            creator: state.creator,
+        },
+        crate::instruction::CloseMatch {},
+    )
+}
+
+pub fn open_dispute_ix(match_account: &Pubkey, player: &Pubkey) -> Instruction {
+    ix(
+        crate::accounts::OpenDispute {
+            dispute: dispute_pda(match_account),
+            match_account: *match_account,
+            player: *player,
+            system_program: system_program::ID,
+        },
+        crate::instruction::OpenDispute { evidence_hash: [2; 32] },
+    )
+}
+
+pub fn resolve_dispute_ix(
+    env: &TestEnv,
+    platform: &Platform,
+    match_account: &Pubkey,
+    resolution: crate::DisputeResolution,
+) -> Instruction {
+    let state = match_state(env, match_account);
+    ix(
+        crate::accounts::ResolveDispute {
+            dispute: dispute_pda(match_account),
+            match_account: *match_account,
+            match_escrow: escrow_pda(match_account),
+            creator: state.creator,
+            joiner: state.joiner,
+            creator_session_vault: funding_vault(state.creator_funding, &state.creator),
+            joiner_session_vault: funding_vault(state.joiner_funding, &state.joiner),
+            abandoner_profile: abandoner_profile(&state),
+            treasury: platform.treasury,
+            referral_pool: platform.referral_pool,
+            config: config_pda(),
+            admin1: platform.admins[0],
+            admin2: platform.admins[1],
+        },
+        crate::instruction::ResolveDispute { resolution },
+    )
+}
+
+// Appends writable remaining_accounts
+pub fn with_targets(mut instruction: Instruction, targets: &[Pubkey]) -> Instruction {
+    instruction
+        .accounts
+        .extend(targets.iter().map(|target| AccountMeta::new(*target, false)));
+    instruction
+}
+
+// The session vault to pass for a player, but only when their stake came from it
+pub fn funding_vault(source: crate::FundingSource, player: &Pubkey) -> Option<Pubkey> {
+    (source == crate::FundingSource::SessionVault).then(|| session_pda(player))
+}
+
+pub fn refund_match_ix(env: &TestEnv, match_account: &Pubkey, keeper: &Pubkey) -> Instruction {
+    let state = match_state(env, match_account);
+    ix(
+        crate::accounts::RefundMatch {
+            match_account: *match_account,
+            match_escrow: escrow_pda(match_account),
+            creator: state.creator,
+            joiner: state.joiner,
+            creator_session_vault: funding_vault(state.creator_funding, &state.creator),
+            joiner_session_vault: funding_vault(state.joiner_funding, &state.joiner),
+            config: config_pda(),
+            keeper: *keeper,
+        },
+        crate::instruction::RefundMatch {},
+    )
+}
+
+// Stand-in for the verifier's signature. The harness trusts ed25519 instructions,
+// so only the bytes the program compares against matter
+pub fn verifier_signature(message: &[u8]) -> [u8; 64] {
+    let digest = anchor_lang::solana_program::hash::hash(message).to_bytes();
+    let mut signature = [0u8; 64];
+    signature[..32].copy_from_slice(&digest);
+    signature[32..].copy_from_slice(&digest);
+    signature
+}
+
+// The verifier's ed25519 instruction over `message`, placed right after the program
+// instruction (relative index 1)
+pub fn signed_by_verifier(platform: &Platform, message: &[u8]) -> ([u8; 64], Instruction) {
+    let signature = verifier_signature(message);
+    (signature, ed25519_instruction(&platform.verifier, &signature, message))
+}
+
+pub fn submit_result_ix(
+    platform: &Platform,
+    match_account: &Pubkey,
+    winner: &Pubkey,
+    result_hash: [u8; 32],
+    signature: [u8; 64],
+) -> Instruction {
+    ix(
+        crate::accounts::SubmitResult {
+            match_account: *match_account,
+            match_escrow: escrow_pda(match_account),
+            winner: *winner,
+            treasury: platform.treasury,
+            referral_pool: platform.referral_pool,
+            config: config_pda(),
+            instruction_sysvar: anchor_lang::solana_program::sysvar::instructions::ID,
+        },
+        crate::instruction::SubmitResult {
+            winner_pubkey: *winner,
+            result_hash,
+            ed25519_signature: signature,
+            _ed25519_recovery_id: 0,
+            ed25519_instruction_index: 1,
+        },
+    )
+}
+
+// A correctly signed submit_result transaction
+pub fn verified_result(
+    platform: &Platform,
+    match_account: &Pubkey,
+    winner: &Pubkey,
+    result_hash: [u8; 32],
+) -> Vec<Instruction> {
+    let message = crate::create_result_message(*match_account, *winner, result_hash);
+    let (signature, ed25519) = signed_by_verifier(platform, &message);
+    vec![submit_result_ix(platform, match_account, winner, result_hash, signature), ed25519]
+}
+
+pub fn submit_draw_ix(
+    env: &TestEnv,
+    platform: &Platform,
+    match_account: &Pubkey,
+    result_hash: [u8; 32],
+    signature: [u8; 64],
+) -> Instruction {
+    let state = match_state(env, match_account);
+    let session_vault = |player: &Pubkey| {
+        let vault = session_pda(player);
+        env.exists(&vault).then_some(vault)
+    };
+    ix(
+        crate::accounts::SubmitDraw {
+            match_account: *match_account,
+            match_escrow: escrow_pda(match_account),
+            creator: state.creator,
+            joiner: state.joiner,
+            creator_session_vault: session_vault(&state.creator),
+            joiner_session_vault: session_vault(&state.joiner),
+            treasury: platform.treasury,
+            referral_pool: platform.referral_pool,
+            config: config_pda(),
+            instruction_sysvar: anchor_lang::solana_program::sysvar::instructions::ID,
+        },
+        crate::instruction::SubmitDraw {
+            result_hash,
+            ed25519_signature: signature,
+            ed25519_instruction_index: 1,
+        },
+    )
+}
+
+// A correctly signed submit_draw transaction
+pub fn verified_draw(
+    env: &TestEnv,
+    platform: &Platform,
+    match_account: &Pubkey,
+    result_hash: [u8; 32],
+) -> Vec<Instruction> {
+    let message = crate::create_draw_message(*match_account, result_hash);
+    let (signature, ed25519) = signed_by_verifier(platform, &message);
+    vec![submit_draw_ix(env, platform, match_account, result_hash, signature), ed25519]
+}
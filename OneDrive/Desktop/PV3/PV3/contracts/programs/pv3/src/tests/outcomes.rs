@@ -0,0 +1,179 @@
+use super::*;
+use crate::{FeePolicy, MatchAborted, MatchDrawn, MatchForfeited, MatchStatus};
+
+fn forfeit_match_ix(platform: &Platform, match_account: &Pubkey, player: &Pubkey, opponent: &Pubkey) -> Instruction {
+    ix(
+        crate::accounts::ForfeitMatch {
+            match_account: *match_account,
+            match_escrow: escrow_pda(match_account),
+            player: *player,
+            opponent: *opponent,
+            treasury: platform.treasury,
+            referral_pool: platform.referral_pool,
+            config: config_pda(),
+        },
+        crate::instruction::ForfeitMatch {},
+    )
+}
+
+fn abort_match_ix(env: &TestEnv, match_account: &Pubkey) -> Instruction {
+    let state = match_state(env, match_account);
+    ix(
+        crate::accounts::AbortMatch {
+            match_account: *match_account,
+            match_escrow: escrow_pda(match_account),
+            creator: state.creator,
+            joiner: state.joiner,
+            creator_session_vault: funding_vault(state.creator_funding, &state.creator),
+            joiner_session_vault: funding_vault(state.joiner_funding, &state.joiner),
+        },
+        crate::instruction::AbortMatch {},
+    )
+}
+
+fn charge_draw_fee(env: &mut TestEnv, platform: &Platform) {
+    env.send(&[ix(
+        crate::accounts::EmergencyAction {
+            config: config_pda(),
+            signer: platform.admins[0],
+        },
+        crate::instruction::UpdateDrawFeePolicy {
+            draw_fee_policy: FeePolicy::NormalFee,
+        },
+    )])
+    .unwrap();
+}
+
+#[test]
+fn forfeit_pays_the_opponent_without_a_verdict() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, joiner) = started_match(&mut env);
+    let balance_before = env.lamports(&creator);
+
+    env.send(&[forfeit_match_ix(&platform, &match_account, &joiner, &creator)]).unwrap();
+
+    let forfeited = &env.events::<MatchForfeited>()[0];
+    assert_eq!(forfeited.forfeited_by, joiner);
+    assert_eq!(forfeited.winner_amount + forfeited.platform_fee, 2 * WAGER);
+    assert_eq!(env.lamports(&creator), balance_before + forfeited.winner_amount);
+    let state = match_state(&env, &match_account);
+    assert!(state.status == MatchStatus::Completed);
+    assert_eq!(state.winner, creator);
+}
+
+#[test]
+fn forfeit_must_come_from_a_player_and_pay_the_other_one() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, joiner) = started_match(&mut env);
+    let stranger = player(&mut env);
+
+    let result = env.send(&[forfeit_match_ix(&platform, &match_account, &stranger, &creator)]);
+    assert_eq!(result, Err(program_error(PV3Error::InvalidParticipant)));
+
+    let result = env.send(&[forfeit_match_ix(&platform, &match_account, &joiner, &stranger)]);
+    assert_eq!(result, Err(program_error(PV3Error::InvalidParticipant)));
+
+    env.send(&[forfeit_match_ix(&platform, &match_account, &joiner, &creator)]).unwrap();
+    let result = env.send(&[forfeit_match_ix(&platform, &match_account, &creator, &joiner)]);
+    assert_eq!(result, Err(program_error(PV3Error::MatchAlreadySettled)));
+}
+
+#[test]
+fn abort_refunds_both_wagers_in_full() {
+    let (mut env, _) = setup();
+    let (match_account, creator, joiner) = started_match(&mut env);
+    let creator_before = env.lamports(&creator);
+    let joiner_before = env.lamports(&joiner);
+
+    env.send(&[abort_match_ix(&env, &match_account)]).unwrap();
+
+    assert_eq!(env.events::<MatchAborted>()[0].refund_amount, WAGER);
+    assert_eq!(env.lamports(&creator), creator_before + WAGER);
+    assert_eq!(env.lamports(&joiner), joiner_before + WAGER);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Aborted);
+}
+
+#[test]
+fn abort_needs_both_players_to_sign() {
+    let (mut env, _) = setup();
+    let (match_account, _, _) = started_match(&mut env);
+    let mut instruction = abort_match_ix(&env, &match_account);
+    instruction.accounts[3].is_signer = false;
+
+    let result = env.send(&[instruction]);
+
+    assert_eq!(result, Err(anchor_error(anchor_lang::error::ErrorCode::AccountNotSigner)));
+}
+
+#[test]
+fn settled_match_cannot_be_aborted() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, _) = started_match(&mut env);
+    env.send(&verified_result(&platform, &match_account, &creator, [1; 32])).unwrap();
+
+    let result = env.send(&[abort_match_ix(&env, &match_account)]);
+
+    assert_eq!(result, Err(program_error(PV3Error::MatchAlreadySettled)));
+}
+
+#[test]
+fn draw_splits_the_pot_evenly_without_a_fee_by_default() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, joiner) = started_match(&mut env);
+    let creator_before = env.lamports(&creator);
+    let joiner_before = env.lamports(&joiner);
+
+    env.send(&verified_draw(&env, &platform, &match_account, [3; 32])).unwrap();
+
+    let drawn = &env.events::<MatchDrawn>()[0];
+    assert_eq!((drawn.creator_amount, drawn.joiner_amount, drawn.platform_fee), (WAGER, WAGER, 0));
+    assert_eq!(env.lamports(&creator), creator_before + WAGER);
+    assert_eq!(env.lamports(&joiner), joiner_before + WAGER);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Drawn);
+}
+
+#[test]
+fn draw_fee_policy_takes_the_platform_fee_before_the_split() {
+    let (mut env, platform) = setup();
+    charge_draw_fee(&mut env, &platform);
+    let (match_account, _, _) = started_match(&mut env);
+    let treasury_before = env.lamports(&platform.treasury);
+
+    env.send(&verified_draw(&env, &platform, &match_account, [3; 32])).unwrap();
+
+    let drawn = &env.events::<MatchDrawn>()[0];
+    assert!(drawn.platform_fee > 0);
+    assert_eq!(drawn.creator_amount + drawn.joiner_amount + drawn.platform_fee, 2 * WAGER);
+    assert_eq!(env.lamports(&platform.treasury), treasury_before + drawn.treasury_fee);
+}
+
+#[test]
+fn unjoined_match_cannot_be_drawn() {
+    let (mut env, platform) = setup();
+    let creator = player(&mut env);
+    let match_account = create_match(&mut env, &creator, &MatchOptions::default());
+
+    let result = env.send(&verified_draw(&env, &platform, &match_account, [3; 32]));
+
+    assert_eq!(result, Err(program_error(PV3Error::MatchNotInProgress)));
+}
+
+#[test]
+fn match_nonce_must_be_the_creators_next_one() {
+    let (mut env, _) = setup();
+    let creator = player(&mut env);
+    let mut instruction = create_match_ix(&env, &creator, &MatchOptions::default());
+    let skipped = match_pda(&creator, 1);
+    instruction.accounts[0].pubkey = skipped;
+    instruction.accounts[3].pubkey = escrow_pda(&skipped);
+    let mut data = crate::instruction::CreateMatch::try_from_slice(&instruction.data[8..]).unwrap();
+    data.nonce = 1;
+    instruction.data = data.data();
+
+    let result = env.send(&[instruction]);
+    assert_eq!(result, Err(program_error(PV3Error::InvalidMatchNonce)));
+
+    let first = create_match(&mut env, &creator, &MatchOptions::default());
+    let second = create_match(&mut env, &creator, &MatchOptions::default());
+    assert_eq!((first, second), (match_pda(&creator, 0), match_pda(&creator, 1)));
+}
@@ -0,0 +1,80 @@
+use super::*;
+use crate::{MatchStatus, RematchCreated};
+
+fn rematch_pda(previous_match: &Pubkey) -> Pubkey {
+    pda(&[b"rematch", previous_match.as_ref()])
+}
+
+fn create_rematch_ix(env: &TestEnv, previous_match: &Pubkey, creator: &Pubkey) -> Instruction {
+    let match_account = rematch_pda(previous_match);
+    ix(
+        crate::accounts::CreateRematch {
+            previous_match: *previous_match,
+            match_account,
+            game_config: game_config_pda(GAME_ID),
+            match_escrow: escrow_pda(&match_account),
+            session_vault: None,
+            config: config_pda(),
+            creator: *creator,
+            system_program: system_program::ID,
+        },
+        crate::instruction::CreateRematch {
+            join_deadline: env.now + JOIN_WINDOW,
+            use_session_vault: false,
+        },
+    )
+}
+
+// A wallet-funded match the creator has already won
+fn completed_match(env: &mut TestEnv, platform: &Platform) -> (Pubkey, Pubkey, Pubkey) {
+    let (match_account, creator, joiner) = started_match(env);
+    env.send(&verified_result(platform, &match_account, &creator, [1; 32])).unwrap();
+    (match_account, creator, joiner)
+}
+
+#[test]
+fn rematch_reuses_the_pairing_game_and_wager() {
+    let (mut env, platform) = setup();
+    let (previous_match, creator, joiner) = completed_match(&mut env, &platform);
+
+    // The loser may call for the rematch too
+    env.send(&[create_rematch_ix(&env, &previous_match, &joiner)]).unwrap();
+
+    let rematch = rematch_pda(&previous_match);
+    let created = &env.events::<RematchCreated>()[0];
+    assert_eq!((created.previous_match, created.opponent), (previous_match, creator));
+    let state = match_state(&env, &rematch);
+    assert_eq!(state.rematch_of, previous_match);
+    assert_eq!(state.wager_amount, WAGER);
+    assert_eq!(state.game_id, GAME_ID);
+    assert_eq!(env.get::<crate::Escrow>(&escrow_pda(&rematch)).balance, WAGER);
+
+    env.send(&[join_match_ix(&rematch, &creator, false, None)]).unwrap();
+    assert!(match_state(&env, &rematch).status == MatchStatus::InProgress);
+}
+
+#[test]
+fn only_the_previous_opponent_can_join_a_rematch() {
+    let (mut env, platform) = setup();
+    let (previous_match, creator, _) = completed_match(&mut env, &platform);
+    let stranger = player(&mut env);
+    env.send(&[create_rematch_ix(&env, &previous_match, &creator)]).unwrap();
+
+    let result = env.send(&[join_match_ix(&rematch_pda(&previous_match), &stranger, false, None)]);
+
+    assert_eq!(result, Err(program_error(PV3Error::NotInvitedOpponent)));
+}
+
+#[test]
+fn rematch_needs_a_settled_match_and_one_of_its_players() {
+    let (mut env, platform) = setup();
+    let (previous_match, creator, _) = started_match(&mut env);
+    let stranger = player(&mut env);
+
+    let result = env.send(&[create_rematch_ix(&env, &previous_match, &creator)]);
+    assert_eq!(result, Err(program_error(PV3Error::RematchNotAllowed)));
+
+    env.send(&verified_result(&platform, &previous_match, &creator, [1; 32])).unwrap();
+    let result = env.send(&[create_rematch_ix(&env, &previous_match, &stranger)]);
+    assert_eq!(result, Err(program_error(PV3Error::InvalidParticipant)));
+}
@@ -0,0 +1,200 @@
+use super::*;
+use crate::{MatchCompleted, MatchDrawn, MatchForfeited, MatchResultPending, MatchStatus};
+
+const RESULT_HASH: [u8; 32] = [6; 32];
+
+fn submit_creator_score(platform: &Platform, match_account: &Pubkey, creator: &Pubkey, score: u64) -> Vec<Instruction> {
+    let message = crate::create_score_message(*match_account, *creator, score, RESULT_HASH);
+    let (signature, ed25519) = signed_by_verifier(platform, &message);
+    let submit = ix(
+        crate::accounts::SubmitCreatorScore {
+            match_account: *match_account,
+            config: config_pda(),
+            instruction_sysvar: anchor_lang::solana_program::sysvar::instructions::ID,
+        },
+        crate::instruction::SubmitCreatorScore {
+            score,
+            result_hash: RESULT_HASH,
+            ed25519_signature: signature,
+            ed25519_instruction_index: 1,
+        },
+    );
+    vec![submit, ed25519]
+}
+
+fn submit_joiner_score(env: &TestEnv, platform: &Platform, match_account: &Pubkey, score: u64) -> Vec<Instruction> {
+    let state = match_state(env, match_account);
+    let message = crate::create_score_message(*match_account, state.joiner, score, RESULT_HASH);
+    let (signature, ed25519) = signed_by_verifier(platform, &message);
+    let submit = ix(
+        crate::accounts::SubmitJoinerScore {
+            match_account: *match_account,
+            match_escrow: escrow_pda(match_account),
+            creator: state.creator,
+            joiner: state.joiner,
+            creator_session_vault: funding_vault(state.creator_funding, &state.creator),
+            joiner_session_vault: funding_vault(state.joiner_funding, &state.joiner),
+            treasury: platform.treasury,
+            referral_pool: platform.referral_pool,
+            config: config_pda(),
+            instruction_sysvar: anchor_lang::solana_program::sysvar::instructions::ID,
+        },
+        crate::instruction::SubmitJoinerScore {
+            score,
+            result_hash: RESULT_HASH,
+            ed25519_signature: signature,
+            ed25519_instruction_index: 1,
+        },
+    );
+    vec![submit, ed25519]
+}
+
+fn claim_score_timeout_ix(platform: &Platform, match_account: &Pubkey, creator: &Pubkey) -> Instruction {
+    ix(
+        crate::accounts::ClaimScoreTimeout {
+            match_account: *match_account,
+            match_escrow: escrow_pda(match_account),
+            creator: *creator,
+            treasury: platform.treasury,
+            referral_pool: platform.referral_pool,
+            config: config_pda(),
+        },
+        crate::instruction::ClaimScoreTimeout {},
+    )
+}
+
+// An async match whose creator posted `score` and was then joined
+fn challenged_score(env: &mut TestEnv, platform: &Platform, score: u64) -> (Pubkey, Pubkey, Pubkey) {
+    let creator = player(env);
+    let joiner = player(env);
+    let options = MatchOptions { is_async: true, ..MatchOptions::default() };
+    let match_account = create_match(env, &creator, &options);
+    env.send(&submit_creator_score(platform, &match_account, &creator, score)).unwrap();
+    env.send(&[join_match_ix(&match_account, &joiner, false, None)]).unwrap();
+    (match_account, creator, joiner)
+}
+
+#[test]
+fn challenger_can_only_join_once_the_creator_posted_a_score() {
+    let (mut env, platform) = setup();
+    let creator = player(&mut env);
+    let joiner = player(&mut env);
+    let options = MatchOptions { is_async: true, ..MatchOptions::default() };
+    let match_account = create_match(&mut env, &creator, &options);
+
+    let result = env.send(&[join_match_ix(&match_account, &joiner, false, None)]);
+    assert_eq!(result, Err(program_error(PV3Error::AwaitingCreatorScore)));
+
+    env.send(&submit_creator_score(&platform, &match_account, &creator, 100)).unwrap();
+    let result = env.send(&submit_creator_score(&platform, &match_account, &creator, 200));
+    assert_eq!(result, Err(program_error(PV3Error::ScoreAlreadySubmitted)));
+
+    env.send(&[join_match_ix(&match_account, &joiner, false, None)]).unwrap();
+    assert_eq!(match_state(&env, &match_account).creator_score, Some(100));
+}
+
+#[test]
+fn higher_score_takes_the_pot() {
+    let (mut env, platform) = setup();
+    let (match_account, _, joiner) = challenged_score(&mut env, &platform, 100);
+    let balance_before = env.lamports(&joiner);
+
+    env.send(&submit_joiner_score(&env, &platform, &match_account, 150)).unwrap();
+
+    let completed = &env.events::<MatchCompleted>()[0];
+    assert_eq!(completed.winner, joiner);
+    assert_eq!(env.lamports(&joiner), balance_before + completed.winner_amount);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Completed);
+}
+
+#[test]
+fn tied_scores_split_the_pot() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, joiner) = challenged_score(&mut env, &platform, 100);
+    let creator_before = env.lamports(&creator);
+    let joiner_before = env.lamports(&joiner);
+
+    env.send(&submit_joiner_score(&env, &platform, &match_account, 100)).unwrap();
+
+    let drawn = &env.events::<MatchDrawn>()[0];
+    assert_eq!(env.lamports(&creator), creator_before + drawn.creator_amount);
+    assert_eq!(env.lamports(&joiner), joiner_before + drawn.joiner_amount);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Drawn);
+}
+
+#[test]
+fn higher_score_waits_out_the_challenge_period() {
+    let (mut env, platform) = setup();
+    enable_challenge_period(&mut env, &platform);
+    let (match_account, _, joiner) = challenged_score(&mut env, &platform, 100);
+    let balance_before = env.lamports(&joiner);
+
+    env.send(&submit_joiner_score(&env, &platform, &match_account, 150)).unwrap();
+
+    assert_eq!(env.events::<MatchResultPending>()[0].winner, joiner);
+    assert_eq!(env.lamports(&joiner), balance_before);
+    let result = env.send(&[finalize_match_ix(&env, &platform, &match_account)]);
+    assert_eq!(result, Err(program_error(PV3Error::ChallengePeriodActive)));
+
+    env.warp(CHALLENGE_PERIOD);
+    env.send(&[finalize_match_ix(&env, &platform, &match_account)]).unwrap();
+
+    let completed = &env.events::<MatchCompleted>()[0];
+    assert_eq!(env.lamports(&joiner), balance_before + completed.winner_amount);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Completed);
+}
+
+#[test]
+fn tied_scores_wait_out_the_challenge_period() {
+    let (mut env, platform) = setup();
+    enable_challenge_period(&mut env, &platform);
+    let (match_account, creator, joiner) = challenged_score(&mut env, &platform, 100);
+    let creator_before = env.lamports(&creator);
+    let joiner_before = env.lamports(&joiner);
+
+    env.send(&submit_joiner_score(&env, &platform, &match_account, 100)).unwrap();
+
+    assert_eq!(env.events::<MatchResultPending>()[0].winner, Pubkey::default());
+    assert!(match_state(&env, &match_account).status == MatchStatus::PendingSettlement);
+    let result = env.send(&[finalize_draw_ix(&env, &platform, &match_account)]);
+    assert_eq!(result, Err(program_error(PV3Error::ChallengePeriodActive)));
+
+    env.warp(CHALLENGE_PERIOD);
+    env.send(&[finalize_draw_ix(&env, &platform, &match_account)]).unwrap();
+
+    let drawn = &env.events::<MatchDrawn>()[0];
+    assert_eq!(env.lamports(&creator), creator_before + drawn.creator_amount);
+    assert_eq!(env.lamports(&joiner), joiner_before + drawn.joiner_amount);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Drawn);
+}
+
+#[test]
+fn async_matches_only_settle_through_scores() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, _) = challenged_score(&mut env, &platform, 100);
+
+    let result = env.send(&verified_result(&platform, &match_account, &creator, RESULT_HASH));
+
+    assert_eq!(result, Err(program_error(PV3Error::ScoreResultRequired)));
+}
+
+#[test]
+fn late_challenger_forfeits_to_the_standing_score() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, _) = challenged_score(&mut env, &platform, 100);
+
+    let result = env.send(&[claim_score_timeout_ix(&platform, &match_account, &creator)]);
+    assert_eq!(result, Err(program_error(PV3Error::ResultDeadlineNotReached)));
+
+    env.warp(RESULT_WINDOW + 1);
+    let result = env.send(&submit_joiner_score(&env, &platform, &match_account, 150));
+    assert_eq!(result, Err(program_error(PV3Error::ScoreDeadlinePassed)));
+
+    let balance_before = env.lamports(&creator);
+    env.send(&[claim_score_timeout_ix(&platform, &match_account, &creator)]).unwrap();
+
+    let forfeited = &env.events::<MatchForfeited>()[0];
+    assert_eq!(forfeited.winner, creator);
+    assert_eq!(env.lamports(&creator), balance_before + forfeited.winner_amount);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Completed);
+}
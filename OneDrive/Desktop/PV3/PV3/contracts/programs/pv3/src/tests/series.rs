@@ -0,0 +1,115 @@
+use super::*;
+use crate::{MatchCompleted, MatchStatus, SeriesGameRecorded};
+
+const RESULT_HASH: [u8; 32] = [9; 32];
+
+fn best_of(series_length: u8) -> MatchOptions {
+    MatchOptions {
+        series_length,
+        ..MatchOptions::default()
+    }
+}
+
+fn started_series(env: &mut TestEnv, series_length: u8) -> (Pubkey, Pubkey, Pubkey) {
+    let creator = player(env);
+    let joiner = player(env);
+    let match_account = create_match(env, &creator, &best_of(series_length));
+    env.send(&[join_match_ix(&match_account, &joiner, false, None)]).unwrap();
+    (match_account, creator, joiner)
+}
+
+fn game_result(
+    platform: &Platform,
+    match_account: &Pubkey,
+    game_number: u8,
+    winner: &Pubkey,
+) -> Vec<Instruction> {
+    let message =
+        crate::create_game_result_message(*match_account, game_number, *winner, RESULT_HASH);
+    let (signature, ed25519) = signed_by_verifier(platform, &message);
+    let mut submit = submit_result_ix(platform, match_account, winner, RESULT_HASH, signature);
+    submit.data = anchor_lang::InstructionData::data(&crate::instruction::SubmitGameResult {
+        game_number,
+        winner_pubkey: *winner,
+        result_hash: RESULT_HASH,
+        ed25519_signature: signature,
+        ed25519_instruction_index: 1,
+    });
+    vec![submit, ed25519]
+}
+
+#[test]
+fn pot_is_released_once_a_side_holds_the_majority() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, joiner) = started_series(&mut env, 3);
+    let balance_before = env.lamports(&creator);
+
+    env.send(&game_result(&platform, &match_account, 1, &creator)).unwrap();
+    env.send(&game_result(&platform, &match_account, 2, &joiner)).unwrap();
+    assert!(match_state(&env, &match_account).status == MatchStatus::InProgress);
+    assert_eq!(env.lamports(&creator), balance_before);
+
+    env.send(&game_result(&platform, &match_account, 3, &creator)).unwrap();
+
+    let game = &env.events::<SeriesGameRecorded>()[0];
+    assert_eq!(game.game_number, 3);
+    assert_eq!((game.creator_wins, game.joiner_wins), (2, 1));
+    let completed = &env.events::<MatchCompleted>()[0];
+    assert_eq!(env.lamports(&creator), balance_before + completed.winner_amount);
+    assert!(match_state(&env, &match_account).status == MatchStatus::Completed);
+}
+
+#[test]
+fn games_must_be_reported_in_order() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, _) = started_series(&mut env, 3);
+    let first_game = game_result(&platform, &match_account, 1, &creator);
+    env.send(&first_game).unwrap();
+
+    // Replaying game one, or skipping ahead, would miscount the series
+    let result = env.send(&first_game);
+    assert_eq!(result, Err(program_error(PV3Error::InvalidGameNumber)));
+
+    let result = env.send(&game_result(&platform, &match_account, 3, &creator));
+    assert_eq!(result, Err(program_error(PV3Error::InvalidGameNumber)));
+}
+
+#[test]
+fn series_and_single_games_use_their_own_instruction() {
+    let (mut env, platform) = setup();
+    let (series, creator, _) = started_series(&mut env, 3);
+    let (single, single_winner, _) = started_match(&mut env);
+
+    let result = env.send(&verified_result(&platform, &series, &creator, RESULT_HASH));
+    assert_eq!(result, Err(program_error(PV3Error::SeriesResultRequired)));
+
+    let result = env.send(&game_result(&platform, &single, 1, &single_winner));
+    assert_eq!(result, Err(program_error(PV3Error::NotASeriesMatch)));
+}
+
+#[test]
+fn series_length_must_be_odd() {
+    let (mut env, _) = setup();
+    let creator = player(&mut env);
+
+    let result = env.send(&[create_match_ix(&env, &creator, &best_of(2))]);
+
+    assert_eq!(result, Err(program_error(PV3Error::InvalidSeriesLength)));
+}
+
+#[test]
+fn fees_cannot_be_redirected_away_from_the_treasury() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, joiner) = started_series(&mut env, 3);
+    env.send(&game_result(&platform, &match_account, 1, &creator)).unwrap();
+
+    let mut deciding_game = game_result(&platform, &match_account, 2, &creator);
+    deciding_game[0].accounts[3].pubkey = joiner;
+    let result = env.send(&deciding_game);
+    assert_eq!(result, Err(anchor_error(anchor_lang::error::ErrorCode::ConstraintAddress)));
+
+    let mut deciding_game = game_result(&platform, &match_account, 2, &creator);
+    deciding_game[0].accounts[4].pubkey = joiner;
+    let result = env.send(&deciding_game);
+    assert_eq!(result, Err(anchor_error(anchor_lang::error::ErrorCode::ConstraintAddress)));
+}
@@ -0,0 +1,156 @@
+use super::*;
+use crate::{MatchCompleted, MatchStatus};
+
+const RESULT_HASH: [u8; 32] = [7; 32];
+
+#[test]
+fn verified_result_pays_the_winner() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, _) = started_match(&mut env);
+    let balance_before = env.lamports(&creator);
+
+    env.send(&verified_result(&platform, &match_account, &creator, RESULT_HASH))
+        .unwrap();
+
+    let state = match_state(&env, &match_account);
+    assert!(state.status == MatchStatus::Completed);
+    let completed = env.events::<MatchCompleted>();
+    assert_eq!(completed.len(), 1);
+    assert_eq!(env.lamports(&creator), balance_before + completed[0].winner_amount);
+}
+
+#[test]
+fn result_signed_for_one_match_cannot_settle_another() {
+    let (mut env, platform) = setup();
+    let (signed_match, creator, joiner) = started_match(&mut env);
+    let other_match = create_match(&mut env, &creator, &MatchOptions::default());
+    env.send(&[join_match_ix(&other_match, &joiner, false, None)]).unwrap();
+
+    // Same winner and hash, but the verifier only ever signed for `signed_match`
+    let message = crate::create_result_message(signed_match, creator, RESULT_HASH);
+    let (signature, ed25519) = signed_by_verifier(&platform, &message);
+    let replay = submit_result_ix(&platform, &other_match, &creator, RESULT_HASH, signature);
+
+    let result = env.send(&[replay, ed25519]);
+
+    assert_eq!(result, Err(program_error(PV3Error::MessageMismatch)));
+    assert!(match_state(&env, &other_match).status == MatchStatus::InProgress);
+}
+
+#[test]
+fn win_signature_cannot_be_replayed_as_a_draw() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, _) = started_match(&mut env);
+
+    let message = crate::create_result_message(match_account, creator, RESULT_HASH);
+    let (signature, ed25519) = signed_by_verifier(&platform, &message);
+    let replay = submit_draw_ix(&env, &platform, &match_account, RESULT_HASH, signature);
+
+    let result = env.send(&[replay, ed25519]);
+
+    assert_eq!(result, Err(program_error(PV3Error::MessageMismatch)));
+}
+
+#[test]
+fn winner_cannot_be_swapped_under_a_valid_signature() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, joiner) = started_match(&mut env);
+
+    let message = crate::create_result_message(match_account, creator, RESULT_HASH);
+    let (signature, ed25519) = signed_by_verifier(&platform, &message);
+    let forged = submit_result_ix(&platform, &match_account, &joiner, RESULT_HASH, signature);
+
+    let result = env.send(&[forged, ed25519]);
+
+    assert_eq!(result, Err(program_error(PV3Error::MessageMismatch)));
+}
+
+#[test]
+fn signature_from_another_key_is_rejected() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, _) = started_match(&mut env);
+    let impostor = env.wallet(SOL);
+
+    let message = crate::create_result_message(match_account, creator, RESULT_HASH);
+    let signature = verifier_signature(&message);
+    let ed25519 = ed25519_instruction(&impostor, &signature, &message);
+    let submit = submit_result_ix(&platform, &match_account, &creator, RESULT_HASH, signature);
+
+    let result = env.send(&[submit, ed25519]);
+
+    assert_eq!(result, Err(program_error(PV3Error::PublicKeyMismatch)));
+}
+
+#[test]
+fn signature_argument_must_match_the_verified_one() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, _) = started_match(&mut env);
+
+    let message = crate::create_result_message(match_account, creator, RESULT_HASH);
+    let (_, ed25519) = signed_by_verifier(&platform, &message);
+    let submit = submit_result_ix(&platform, &match_account, &creator, RESULT_HASH, [9; 64]);
+
+    let result = env.send(&[submit, ed25519]);
+
+    assert_eq!(result, Err(program_error(PV3Error::SignatureMismatch)));
+}
+
+#[test]
+fn signature_data_from_another_instruction_is_rejected() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, _) = started_match(&mut env);
+
+    let message = crate::create_result_message(match_account, creator, RESULT_HASH);
+    let (signature, mut ed25519) = signed_by_verifier(&platform, &message);
+    // Point the message at instruction 0, so the precompile checks bytes the program never sees
+    ed25519.data[14..16].copy_from_slice(&0u16.to_le_bytes());
+    let submit = submit_result_ix(&platform, &match_account, &creator, RESULT_HASH, signature);
+
+    let result = env.send(&[submit, ed25519]);
+
+    assert_eq!(result, Err(program_error(PV3Error::InvalidSignatureData)));
+}
+
+#[test]
+fn instruction_index_must_point_at_the_ed25519_program() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, _) = started_match(&mut env);
+
+    let message = crate::create_result_message(match_account, creator, RESULT_HASH);
+    let (signature, ed25519) = signed_by_verifier(&platform, &message);
+    let mut submit = submit_result_ix(&platform, &match_account, &creator, RESULT_HASH, signature);
+    // Relative index 0 is the submit instruction itself
+    *submit.data.last_mut().unwrap() = 0;
+
+    let result = env.send(&[submit, ed25519]);
+
+    assert_eq!(result, Err(program_error(PV3Error::InvalidSignatureInstruction)));
+}
+
+#[test]
+fn only_the_configured_verifiers_signature_is_accepted() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, _) = started_match(&mut env);
+    let impostor = Platform {
+        admins: platform.admins,
+        treasury: platform.treasury,
+        referral_pool: platform.referral_pool,
+        verifier: env.wallet(SOL),
+    };
+
+    let result = env.send(&verified_result(&impostor, &match_account, &creator, RESULT_HASH));
+
+    assert_eq!(result, Err(program_error(PV3Error::PublicKeyMismatch)));
+}
+
+#[test]
+fn settled_result_cannot_be_submitted_twice() {
+    let (mut env, platform) = setup();
+    let (match_account, creator, _) = started_match(&mut env);
+    let submit = verified_result(&platform, &match_account, &creator, RESULT_HASH);
+
+    env.send(&submit).unwrap();
+    let result = env.send(&submit);
+
+    assert_eq!(result, Err(program_error(PV3Error::MatchAlreadySettled)));
+}
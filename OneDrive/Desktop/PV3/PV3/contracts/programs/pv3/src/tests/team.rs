@@ -0,0 +1,358 @@
+use super::*;
+use crate::{
+    FlaggedTeamMatchResolved, FundingSource, MatchStatus, SessionVault, TeamMatch,
+    TeamMatchCompleted, TeamMatchResultPending, TeamSide,
+};
+
+const RESULT_HASH: [u8; 32] = [4; 32];
+
+fn team_match_pda(creator: &Pubkey, nonce: u64) -> Pubkey {
+    pda(&[b"team_match", creator.as_ref(), &nonce.to_le_bytes()])
+}
+
+fn create_team_match(env: &mut TestEnv, creator: &Pubkey, use_session_vault: bool) -> Pubkey {
+    let nonce = env.get::<crate::PlayerProfile>(&profile_pda(creator)).match_nonce;
+    let team_match = team_match_pda(creator, nonce);
+    env.send(&[ix(
+        crate::accounts::CreateTeamMatch {
+            team_match,
+            creator_profile: profile_pda(creator),
+            game_config: game_config_pda(GAME_ID),
+            team_escrow: escrow_pda(&team_match),
+            session_vault: use_session_vault.then(|| session_pda(creator)),
+            config: config_pda(),
+            creator: *creator,
+            system_program: system_program::ID,
+        },
+        crate::instruction::CreateTeamMatch {
+            nonce,
+            game_id: GAME_ID.to_string(),
+            wager_amount: WAGER,
+            join_deadline: env.now + JOIN_WINDOW,
+            result_window: RESULT_WINDOW,
+            use_session_vault,
+        },
+    )])
+    .unwrap();
+    team_match
+}
+
+fn join_team_match(
+    env: &mut TestEnv,
+    team_match: &Pubkey,
+    player: &Pubkey,
+    side: TeamSide,
+    use_session_vault: bool,
+) {
+    env.send(&[ix(
+        crate::accounts::JoinTeamMatch {
+            team_match: *team_match,
+            team_escrow: escrow_pda(team_match),
+            session_vault: use_session_vault.then(|| session_pda(player)),
+            config: config_pda(),
+            player: *player,
+            system_program: system_program::ID,
+        },
+        crate::instruction::JoinTeamMatch { side, use_session_vault },
+    )])
+    .unwrap();
+}
+
+// Each member's wallet, or the session vault that funded their share
+fn side_targets(env: &TestEnv, team_match: &Pubkey, side: TeamSide) -> Vec<Pubkey> {
+    let state = env.get::<TeamMatch>(team_match);
+    state
+        .team(side)
+        .iter()
+        .zip(state.team_funding(side))
+        .map(|(member, source)| funding_vault(*source, member).unwrap_or(*member))
+        .collect()
+}
+
+fn submit_team_result(
+    platform: &Platform,
+    team_match: &Pubkey,
+    winning_team: TeamSide,
+    recipients: &[Pubkey],
+) -> Vec<Instruction> {
+    let message = crate::team::create_team_result_message(*team_match, winning_team, RESULT_HASH);
+    let (signature, ed25519) = signed_by_verifier(platform, &message);
+    let submit = ix(
+        crate::accounts::SubmitTeamResult {
+            team_match: *team_match,
+            team_escrow: escrow_pda(team_match),
+            treasury: platform.treasury,
+            referral_pool: platform.referral_pool,
+            config: config_pda(),
+            instruction_sysvar: anchor_lang::solana_program::sysvar::instructions::ID,
+        },
+        crate::instruction::SubmitTeamResult {
+            winning_team,
+            result_hash: RESULT_HASH,
+            ed25519_signature: signature,
+            ed25519_instruction_index: 1,
+        },
+    );
+    vec![with_targets(submit, recipients), ed25519]
+}
+
+fn refund_team_match_ix(team_match: &Pubkey, targets: &[Pubkey]) -> Instruction {
+    with_targets(
+        ix(
+            crate::accounts::RefundTeamMatch {
+                team_match: *team_match,
+                team_escrow: escrow_pda(team_match),
+            },
+            crate::instruction::RefundTeamMatch {},
+        ),
+        targets,
+    )
+}
+
+fn finalize_team_match_ix(
+    platform: &Platform,
+    team_match: &Pubkey,
+    recipients: &[Pubkey],
+) -> Instruction {
+    with_targets(
+        ix(
+            crate::accounts::FinalizeTeamMatch {
+                team_match: *team_match,
+                team_escrow: escrow_pda(team_match),
+                treasury: platform.treasury,
+                referral_pool: platform.referral_pool,
+                config: config_pda(),
+            },
+            crate::instruction::FinalizeTeamMatch {},
+        ),
+        recipients,
+    )
+}
+
+fn flag_team_match_ix(team_match: &Pubkey, flagger: &Pubkey) -> Instruction {
+    ix(
+        crate::accounts::FlagTeamMatch {
+            team_match: *team_match,
+            config: config_pda(),
+            flagger: *flagger,
+        },
+        crate::instruction::FlagTeamMatch {},
+    )
+}
+
+fn resolve_flagged_team_match_ix(
+    platform: &Platform,
+    team_match: &Pubkey,
+    release_payout: bool,
+    targets: &[Pubkey],
+) -> Instruction {
+    with_targets(
+        ix(
+            crate::accounts::ResolveFlaggedTeamMatch {
+                team_match: *team_match,
+                team_escrow: escrow_pda(team_match),
+                treasury: platform.treasury,
+                referral_pool: platform.referral_pool,
+                config: config_pda(),
+                admin1: platform.admins[0],
+                admin2: platform.admins[1],
+            },
+            crate::instruction::ResolveFlaggedTeamMatch { release_payout },
+        ),
+        targets,
+    )
+}
+
+// A full 2v2 where the creator and one team B member staked from session vaults
+fn started_team_match(env: &mut TestEnv) -> (Pubkey, [Pubkey; 4]) {
+    let players = [player(env), player(env), player(env), player(env)];
+    fund_session(env, &players[0], SOL);
+    fund_session(env, &players[2], SOL);
+    let team_match = create_team_match(env, &players[0], true);
+    join_team_match(env, &team_match, &players[1], TeamSide::A, false);
+    join_team_match(env, &team_match, &players[2], TeamSide::B, true);
+    join_team_match(env, &team_match, &players[3], TeamSide::B, false);
+    (team_match, players)
+}
+
+#[test]
+fn members_record_their_funding_source() {
+    let (mut env, _) = setup();
+    let (team_match, _) = started_team_match(&mut env);
+
+    let state = env.get::<TeamMatch>(&team_match);
+    assert!(state.status == MatchStatus::InProgress);
+    assert!(state.team_a_funding == vec![FundingSource::SessionVault, FundingSource::Wallet]);
+    assert!(state.team_b_funding == vec![FundingSource::SessionVault, FundingSource::Wallet]);
+}
+
+#[test]
+fn winning_team_shares_go_back_to_each_members_funding_source() {
+    let (mut env, platform) = setup();
+    let (team_match, players) = started_team_match(&mut env);
+    let vault_before = env.get::<SessionVault>(&session_pda(&players[2])).balance;
+    let wallet_before = env.lamports(&players[3]);
+
+    let recipients = side_targets(&env, &team_match, TeamSide::B);
+    env.send(&submit_team_result(&platform, &team_match, TeamSide::B, &recipients)).unwrap();
+
+    let completed = &env.events::<TeamMatchCompleted>()[0];
+    assert_eq!(completed.winners, [players[2], players[3]]);
+    let vault = env.get::<SessionVault>(&session_pda(&players[2]));
+    assert_eq!(vault.balance, vault_before + completed.share_amounts[0]);
+    assert_eq!(env.lamports(&players[3]), wallet_before + completed.share_amounts[1]);
+    assert!(env.get::<TeamMatch>(&team_match).status == MatchStatus::Completed);
+}
+
+#[test]
+fn shares_cannot_be_paid_to_the_losing_team() {
+    let (mut env, platform) = setup();
+    let (team_match, _) = started_team_match(&mut env);
+
+    let losers = side_targets(&env, &team_match, TeamSide::A);
+    let result = env.send(&submit_team_result(&platform, &team_match, TeamSide::B, &losers));
+
+    assert_eq!(result, Err(program_error(PV3Error::InvalidParticipant)));
+}
+
+#[test]
+fn expired_team_match_refunds_session_shares_into_their_vaults() {
+    let (mut env, _) = setup();
+    let (team_match, players) = started_team_match(&mut env);
+    let mut targets = side_targets(&env, &team_match, TeamSide::A);
+    targets.extend(side_targets(&env, &team_match, TeamSide::B));
+    let vault_before = env.get::<SessionVault>(&session_pda(&players[0])).balance;
+    let wallet_before = env.lamports(&players[3]);
+
+    let result = env.send(&[refund_team_match_ix(&team_match, &targets)]);
+    assert_eq!(result, Err(program_error(PV3Error::ResultDeadlineNotReached)));
+
+    env.warp(RESULT_WINDOW + 1);
+    env.send(&[refund_team_match_ix(&team_match, &targets)]).unwrap();
+
+    let vault = env.get::<SessionVault>(&session_pda(&players[0]));
+    assert_eq!(vault.balance, vault_before + WAGER);
+    assert_eq!(env.lamports(&players[3]), wallet_before + WAGER);
+    assert!(env.get::<TeamMatch>(&team_match).status == MatchStatus::Refunded);
+}
+
+#[test]
+fn refund_of_a_session_share_requires_the_vault() {
+    let (mut env, _) = setup();
+    let (team_match, players) = started_team_match(&mut env);
+
+    env.warp(RESULT_WINDOW + 1);
+    let result = env.send(&[refund_team_match_ix(&team_match, &players)]);
+
+    assert_eq!(result, Err(anchor_error(anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram)));
+}
+
+#[test]
+fn team_result_waits_out_the_challenge_period() {
+    let (mut env, platform) = setup();
+    enable_challenge_period(&mut env, &platform);
+    let (team_match, players) = started_team_match(&mut env);
+    let wallet_before = env.lamports(&players[3]);
+
+    env.send(&submit_team_result(&platform, &team_match, TeamSide::B, &[])).unwrap();
+
+    let pending = &env.events::<TeamMatchResultPending>()[0];
+    assert!(pending.winning_team == TeamSide::B);
+    assert!(env.get::<TeamMatch>(&team_match).status == MatchStatus::PendingSettlement);
+
+    let recipients = side_targets(&env, &team_match, TeamSide::B);
+    let result = env.send(&[finalize_team_match_ix(&platform, &team_match, &recipients)]);
+    assert_eq!(result, Err(program_error(PV3Error::ChallengePeriodActive)));
+
+    env.warp(CHALLENGE_PERIOD);
+    env.send(&[finalize_team_match_ix(&platform, &team_match, &recipients)]).unwrap();
+
+    let completed = &env.events::<TeamMatchCompleted>()[0];
+    assert_eq!(env.lamports(&players[3]), wallet_before + completed.share_amounts[1]);
+    assert!(env.get::<TeamMatch>(&team_match).status == MatchStatus::Completed);
+}
+
+#[test]
+fn only_the_losing_team_can_flag_a_result() {
+    let (mut env, platform) = setup();
+    enable_challenge_period(&mut env, &platform);
+    let (team_match, players) = started_team_match(&mut env);
+    env.send(&submit_team_result(&platform, &team_match, TeamSide::B, &[])).unwrap();
+
+    let result = env.send(&[flag_team_match_ix(&team_match, &players[3])]);
+    assert_eq!(result, Err(program_error(PV3Error::UnauthorizedFlag)));
+
+    env.send(&[flag_team_match_ix(&team_match, &players[1])]).unwrap();
+    assert!(env.get::<TeamMatch>(&team_match).status == MatchStatus::Flagged);
+}
+
+#[test]
+fn dismissed_flag_releases_the_team_payout() {
+    let (mut env, platform) = setup();
+    enable_challenge_period(&mut env, &platform);
+    let (team_match, players) = started_team_match(&mut env);
+    env.send(&submit_team_result(&platform, &team_match, TeamSide::B, &[])).unwrap();
+    env.send(&[flag_team_match_ix(&team_match, &players[0])]).unwrap();
+
+    let recipients = side_targets(&env, &team_match, TeamSide::B);
+    let result = env.send(&[finalize_team_match_ix(&platform, &team_match, &recipients)]);
+    assert_eq!(result, Err(program_error(PV3Error::MatchFlagged)));
+
+    let vault_before = env.get::<SessionVault>(&session_pda(&players[2])).balance;
+    env.send(&[resolve_flagged_team_match_ix(&platform, &team_match, true, &recipients)]).unwrap();
+
+    let completed = &env.events::<TeamMatchCompleted>()[0];
+    let vault = env.get::<SessionVault>(&session_pda(&players[2]));
+    assert_eq!(vault.balance, vault_before + completed.share_amounts[0]);
+    assert!(env.events::<FlaggedTeamMatchResolved>()[0].payout_released);
+    assert!(env.get::<TeamMatch>(&team_match).status == MatchStatus::Completed);
+}
+
+#[test]
+fn team_matches_share_the_match_number_sequence_and_can_be_indexed() {
+    let (mut env, _) = setup();
+    let creator = player(&mut env);
+    let match_account = create_match(&mut env, &creator, &MatchOptions::default());
+    let team_match = create_team_match(&mut env, &creator, false);
+    let match_number = env.get::<TeamMatch>(&team_match).match_number;
+    assert_eq!(match_number, match_state(&env, &match_account).match_number + 1);
+
+    env.send(&[ix(
+        crate::accounts::IndexTeamMatch {
+            team_match,
+            match_index: match_index_pda(match_number),
+            creator,
+            system_program: system_program::ID,
+        },
+        crate::instruction::IndexTeamMatch {},
+    )])
+    .unwrap();
+
+    let index = env.get::<crate::MatchIndex>(&match_index_pda(match_number));
+    assert_eq!(index.match_account, team_match);
+}
+
+#[test]
+fn completed_team_match_can_be_closed() {
+    let (mut env, platform) = setup();
+    let (team_match, players) = started_team_match(&mut env);
+    let recipients = side_targets(&env, &team_match, TeamSide::A);
+    env.send(&submit_team_result(&platform, &team_match, TeamSide::A, &recipients)).unwrap();
+    let match_number = env.get::<TeamMatch>(&team_match).match_number;
+    let rent = env.lamports(&team_match) + env.lamports(&escrow_pda(&team_match));
+    let balance_before = env.lamports(&players[0]);
+
+    env.send(&[ix(
+        crate::accounts::CloseTeamMatch {
+            team_match,
+            team_escrow: escrow_pda(&team_match),
+            match_index: match_index_pda(match_number),
+            creator: players[0],
+        },
+        crate::instruction::CloseTeamMatch {},
+    )])
+    .unwrap();
+
+    assert!(!env.exists(&team_match));
+    assert_eq!(env.lamports(&players[0]), balance_before + rent);
+}
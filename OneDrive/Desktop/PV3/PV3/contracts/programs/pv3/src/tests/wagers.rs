@@ -0,0 +1,164 @@
+use super::*;
+use crate::{GameConfig, MatchCreated, WagerOutOfBounds, DEFAULT_WAGER_TIERS};
+
+fn update_wager_bounds_ix(admin: &Pubkey, min_wager: u64, max_wager: u64) -> Instruction {
+    ix(
+        crate::accounts::EmergencyAction {
+            config: config_pda(),
+            signer: *admin,
+        },
+        crate::instruction::UpdateWagerBounds { min_wager, max_wager },
+    )
+}
+
+fn update_wager_tiers_ix(admin: &Pubkey, wager_tiers: Vec<u64>) -> Instruction {
+    ix(
+        crate::accounts::EmergencyAction {
+            config: config_pda(),
+            signer: *admin,
+        },
+        crate::instruction::UpdateWagerTiers { wager_tiers },
+    )
+}
+
+fn create_game_config_ix(admin: &Pubkey, min_wager: u64, max_wager: u64) -> Instruction {
+    ix(
+        crate::accounts::CreateGameConfig {
+            game_config: game_config_pda(GAME_ID),
+            config: config_pda(),
+            signer: *admin,
+            system_program: system_program::ID,
+        },
+        crate::instruction::CreateGameConfig {
+            game_id: GAME_ID.to_string(),
+            min_wager,
+            max_wager,
+        },
+    )
+}
+
+fn update_game_wager_bounds_ix(admin: &Pubkey, min_wager: u64, max_wager: u64) -> Instruction {
+    ix(
+        crate::accounts::UpdateGameConfig {
+            game_config: game_config_pda(GAME_ID),
+            config: config_pda(),
+            signer: *admin,
+        },
+        crate::instruction::UpdateGameWagerBounds { min_wager, max_wager },
+    )
+}
+
+fn wager(wager: u64) -> MatchOptions {
+    MatchOptions { wager, ..MatchOptions::default() }
+}
+
+#[test]
+fn wagers_must_sit_within_the_platform_bounds() {
+    let (mut env, platform) = setup();
+    let creator = player(&mut env);
+    env.send(&[update_wager_tiers_ix(&platform.admins[0], vec![2 * WAGER, 5 * WAGER])]).unwrap();
+    env.send(&[update_wager_bounds_ix(&platform.admins[0], 2 * WAGER, 5 * WAGER)]).unwrap();
+
+    let result = env.send(&[create_match_ix(&env, &creator, &wager(WAGER))]);
+    assert_eq!(result, Err(program_error(PV3Error::WagerTooLow)));
+
+    let result = env.send(&[create_match_ix(&env, &creator, &wager(10 * WAGER))]);
+    assert_eq!(result, Err(program_error(PV3Error::WagerTooHigh)));
+
+    create_match(&mut env, &creator, &wager(5 * WAGER));
+}
+
+#[test]
+fn rejected_wager_logs_the_active_bounds() {
+    let (mut env, platform) = setup();
+    let creator = player(&mut env);
+    env.send(&[create_game_config_ix(&platform.admins[0], 5 * WAGER, 10 * WAGER)]).unwrap();
+
+    let result = env.send(&[create_match_ix(&env, &creator, &wager(WAGER))]);
+
+    assert_eq!(result, Err(program_error(PV3Error::WagerTooLow)));
+    let bounds = &env.events::<WagerOutOfBounds>()[0];
+    assert_eq!((bounds.wager_amount, bounds.min_wager, bounds.max_wager), (WAGER, 5 * WAGER, 10 * WAGER));
+}
+
+#[test]
+fn wager_bounds_are_admin_only_and_must_be_ordered() {
+    let (mut env, platform) = setup();
+    let outsider = env.wallet(SOL);
+
+    let result = env.send(&[update_wager_bounds_ix(&outsider, WAGER, SOL)]);
+    assert_eq!(result, Err(program_error(PV3Error::UnauthorizedAdmin)));
+
+    for (min_wager, max_wager) in [(0, SOL), (SOL, WAGER)] {
+        let result = env.send(&[update_wager_bounds_ix(&platform.admins[0], min_wager, max_wager)]);
+        assert_eq!(result, Err(program_error(PV3Error::InvalidWagerBounds)));
+    }
+}
+
+#[test]
+fn game_config_overrides_the_platform_bounds() {
+    let (mut env, platform) = setup();
+    let creator = player(&mut env);
+    env.send(&[create_game_config_ix(&platform.admins[0], 5 * WAGER, 10 * WAGER)]).unwrap();
+    assert_eq!(env.get::<GameConfig>(&game_config_pda(GAME_ID)).min_wager, 5 * WAGER);
+
+    let result = env.send(&[create_match_ix(&env, &creator, &wager(WAGER))]);
+    assert_eq!(result, Err(program_error(PV3Error::WagerTooLow)));
+
+    env.send(&[update_game_wager_bounds_ix(&platform.admins[1], WAGER, 10 * WAGER)]).unwrap();
+    create_match(&mut env, &creator, &wager(WAGER));
+}
+
+#[test]
+fn wager_must_be_one_of_the_tiers() {
+    let (mut env, _) = setup();
+    let creator = player(&mut env);
+
+    let result = env.send(&[create_match_ix(&env, &creator, &wager(WAGER + 1))]);
+    assert_eq!(result, Err(program_error(PV3Error::WagerNotATier)));
+
+    let tier_wager = DEFAULT_WAGER_TIERS[3];
+    create_match(&mut env, &creator, &wager(tier_wager));
+    assert_eq!(env.events::<MatchCreated>()[0].wager_tier, Some(3));
+}
+
+#[test]
+fn empty_tier_list_allows_any_wager_within_the_bounds() {
+    let (mut env, platform) = setup();
+    let creator = player(&mut env);
+    env.send(&[update_wager_tiers_ix(&platform.admins[0], Vec::new())]).unwrap();
+
+    create_match(&mut env, &creator, &wager(WAGER + 1));
+
+    assert_eq!(env.events::<MatchCreated>()[0].wager_tier, None);
+}
+
+#[test]
+fn tiers_must_be_non_zero_and_ascending() {
+    let (mut env, platform) = setup();
+
+    for wager_tiers in [vec![0, WAGER], vec![2 * WAGER, WAGER], vec![WAGER, WAGER]] {
+        let result = env.send(&[update_wager_tiers_ix(&platform.admins[0], wager_tiers)]);
+        assert_eq!(result, Err(program_error(PV3Error::InvalidWagerTiers)));
+    }
+
+    let outsider = env.wallet(SOL);
+    let result = env.send(&[update_wager_tiers_ix(&outsider, vec![WAGER])]);
+    assert_eq!(result, Err(program_error(PV3Error::UnauthorizedAdmin)));
+}
+
+#[test]
+fn tiers_and_bounds_must_agree() {
+    let (mut env, platform) = setup();
+
+    // Default bounds are 0.1 to 10 SOL
+    let result = env.send(&[update_wager_tiers_ix(&platform.admins[0], vec![WAGER, 11 * SOL])]);
+    assert_eq!(result, Err(program_error(PV3Error::InvalidWagerTiers)));
+
+    // The default tiers run from 0.1 to 10 SOL, narrowing past them would strand some
+    let result = env.send(&[update_wager_bounds_ix(&platform.admins[0], 2 * WAGER, 10 * SOL)]);
+    assert_eq!(result, Err(program_error(PV3Error::InvalidWagerBounds)));
+
+    env.send(&[update_wager_tiers_ix(&platform.admins[0], vec![2 * WAGER, SOL])]).unwrap();
+    env.send(&[update_wager_bounds_ix(&platform.admins[0], 2 * WAGER, SOL)]).unwrap();
+}
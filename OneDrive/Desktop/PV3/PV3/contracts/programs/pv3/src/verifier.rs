@@ -1,35 +0,0 @@
-// Verifier module - handles match result verification and signatures
-// Will implement ed25519 signature verification for game outcomes
-
-use anchor_lang::prelude::*;
-
-// Placeholder structures for verifier functionality
-#[account]
-pub struct VerifierConfig {
-    pub verifier_pubkey: Pubkey,
-    pub is_active: bool,
-}
-
-impl VerifierConfig {
-    pub const SIZE: usize = 32 + 1 + 8;
-}
-
-// Placeholder error enum
-#[error_code]
-pub enum VerifierError {
-    #[msg("Invalid verifier signature")]
-    InvalidSignature,
-    #[msg("Verifier is not active")]
-    VerifierNotActive,
-}
-
-// Placeholder for signature verification - to be implemented
-pub fn verify_ed25519_signature(
-    _signature: &[u8; 64],
-    _message: &[u8],
-    _pubkey: &[u8; 32],
-) -> Result<bool> {
-    // TODO: Implement proper ed25519 signature verification
-    // For now, return true as placeholder
-    Ok(true)
-} 
\ No newline at end of file